@@ -1,13 +1,49 @@
-use std::io::{Read, Seek, SeekFrom};
+use std::{
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
 
 use anyhow::bail;
-use flate2::bufread::{GzDecoder, ZlibDecoder};
+use flate2::{
+    bufread::{GzDecoder, ZlibDecoder},
+    write::ZlibEncoder,
+    Compression,
+};
 
-use crate::{chunk_format::Chunk, de::from_slice};
+use crate::{chunk_format::Chunk, de::from_slice, ser::to_writer};
 
 const SECTOR_SIZE: usize = 4 * 1024;
 
-fn parse_chunk<R>(reader: &mut R) -> anyhow::Result<Chunk>
+/// Set on the compression type byte when the chunk's payload is stored
+/// externally in a sibling `c.<x>.<z>.mcc` file instead of inline in the
+/// region, because it was too large to fit in the region file's sector
+/// granularity.
+const EXTERNAL_CHUNK_FLAG: u8 = 0x80;
+
+/// Decompresses a Zstandard-compressed chunk payload. Gated behind the
+/// `zstd` feature, since it's an optional codec most worlds never use.
+#[cfg(feature = "zstd")]
+fn decode_zstd(buf: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut decoder = zstd::stream::read::Decoder::new(buf)?;
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decode_zstd(_buf: &[u8]) -> anyhow::Result<Vec<u8>> {
+    bail!("chunk is Zstandard-compressed, but the \"zstd\" feature is not enabled");
+}
+
+/// Reads the oversized-chunk payload for the chunk at region-relative `x`,
+/// `z` from its sibling `c.<x>.<z>.mcc` file in `region_dir`.
+fn read_external_chunk(region_dir: &Path, x: u8, z: u8) -> anyhow::Result<Vec<u8>> {
+    Ok(std::fs::read(
+        region_dir.join(format!("c.{}.{}.mcc", x, z)),
+    )?)
+}
+
+fn parse_chunk<R>(reader: &mut R, region_dir: &Path, x: u8, z: u8) -> anyhow::Result<Chunk>
 where
     R: Read,
 {
@@ -19,6 +55,12 @@ where
     let mut buf = vec![0; length - 1];
     reader.read_exact(&mut buf)?;
 
+    let external = compression_type & EXTERNAL_CHUNK_FLAG != 0;
+    let compression_type = compression_type & !EXTERNAL_CHUNK_FLAG;
+    if external {
+        buf = read_external_chunk(region_dir, x, z)?;
+    }
+
     let chunk = match compression_type {
         1 => {
             let mut gz = GzDecoder::new(&buf[..]);
@@ -39,6 +81,7 @@ where
             lz4.read_to_end(&mut buf)?;
             from_slice(&buf)
         }
+        5 => from_slice(&decode_zstd(&buf)?),
         _ => bail!(
             "unknown compression type for Anvil file: {}",
             compression_type
@@ -48,9 +91,11 @@ where
     Ok(chunk?)
 }
 
-/// Parses the chunk at the given x and z region-relative chunk coordinates from
-/// the region file. x and z should be in the range 0..32.
-pub fn parse_chunk_at<R>(reader: &mut R, x: u8, z: u8) -> anyhow::Result<Chunk>
+/// Parses the chunk at the given x and z region-relative chunk coordinates
+/// from the region file. x and z should be in the range 0..32. `region_dir`
+/// is the directory containing the region file, consulted for a sibling
+/// `c.<x>.<z>.mcc` file if the chunk's payload is stored externally.
+pub fn parse_chunk_at<R>(reader: &mut R, region_dir: &Path, x: u8, z: u8) -> anyhow::Result<Chunk>
 where
     R: Read + Seek,
 {
@@ -64,41 +109,282 @@ where
         bail!("chunk not present in region file");
     }
     reader.seek(SeekFrom::Start(location as u64 * SECTOR_SIZE as u64))?;
-    parse_chunk(reader)
+    parse_chunk(reader, region_dir, x, z)
+}
+
+/// A chunk's location within a region file, as stored in the 4 KiB location
+/// header: the sector (`SECTOR_SIZE`-byte block) it starts at, and how many
+/// sectors it spans.
+#[derive(Debug, Clone, Copy)]
+struct ChunkLocation {
+    sector_offset: u32,
+    sector_count: u8,
 }
 
-pub fn parse_region<R>(reader: &mut R) -> anyhow::Result<Vec<Chunk>>
+fn location_index(x: u8, z: u8) -> usize {
+    z as usize * 32 + x as usize
+}
+
+/// A length-limited view into `reader`, starting at byte offset `base`, so a
+/// chunk's decompressor can read as if it owned the reader without straying
+/// into a neighboring chunk's sectors. `parse_chunk`'s decompressors are all
+/// `Read`-only, so unlike decomp-toolkit's `take_seek` helper this only needs
+/// to implement `Read`, not `Seek`, over the bounded view.
+struct Take<'r, R> {
+    reader: &'r mut R,
+    limit: u64,
+    pos: u64,
+}
+
+impl<'r, R> Take<'r, R>
+where
+    R: Seek,
+{
+    fn new(reader: &'r mut R, base: u64, limit: u64) -> io::Result<Self> {
+        reader.seek(SeekFrom::Start(base))?;
+        Ok(Take {
+            reader,
+            limit,
+            pos: 0,
+        })
+    }
+}
+
+impl<R> Read for Take<'_, R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.limit.saturating_sub(self.pos);
+        let max_len = remaining.min(buf.len() as u64) as usize;
+        let read = self.reader.read(&mut buf[..max_len])?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+/// Parses a region file's 8 KiB location/timestamp header once, then parses
+/// individual chunks on demand by seeking, instead of eagerly decompressing
+/// every present chunk into memory up front like the old `parse_region` did.
+pub struct RegionReader<R> {
+    reader: R,
+    region_dir: PathBuf,
+    locations: Vec<Option<ChunkLocation>>,
+    timestamps: Vec<u32>,
+}
+
+impl<R> RegionReader<R>
 where
     R: Read + Seek,
 {
-    let mut locations = [0; SECTOR_SIZE];
-    reader.read_exact(&mut locations)?;
-    let mut timestamps = [0; SECTOR_SIZE];
-    reader.read_exact(&mut timestamps)?;
-
-    let mut chunk_locations = Vec::new();
-    for z in 0..32usize {
-        for x in 0..32usize {
-            let offset = (z * 32 + x) * 4;
-            // First three bytes are big endian offset in sectors into file
-            let location = u32::from_be_bytes([
+    /// `region_dir` is the directory containing the region file, consulted
+    /// for sibling `c.<x>.<z>.mcc` files for any chunks whose payload is
+    /// stored externally.
+    pub fn new(mut reader: R, region_dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let mut locations_buf = [0; SECTOR_SIZE];
+        reader.read_exact(&mut locations_buf)?;
+        let mut timestamps_buf = [0; SECTOR_SIZE];
+        reader.read_exact(&mut timestamps_buf)?;
+
+        let mut locations = Vec::with_capacity(1024);
+        let mut timestamps = Vec::with_capacity(1024);
+        for i in 0..1024 {
+            let offset = i * 4;
+            let sector_offset = u32::from_be_bytes([
                 0,
-                locations[offset],
-                locations[offset + 1],
-                locations[offset + 2],
+                locations_buf[offset],
+                locations_buf[offset + 1],
+                locations_buf[offset + 2],
             ]);
+            let sector_count = locations_buf[offset + 3];
+            locations.push((sector_offset != 0).then_some(ChunkLocation {
+                sector_offset,
+                sector_count,
+            }));
+            timestamps.push(u32::from_be_bytes([
+                timestamps_buf[offset],
+                timestamps_buf[offset + 1],
+                timestamps_buf[offset + 2],
+                timestamps_buf[offset + 3],
+            ]));
+        }
 
-            if location != 0 {
-                chunk_locations.push(location);
-            }
+        Ok(RegionReader {
+            reader,
+            region_dir: region_dir.into(),
+            locations,
+            timestamps,
+        })
+    }
+
+    /// The Unix timestamp the chunk at region-relative `x`, `z` was last
+    /// saved, or `None` if no chunk is present there.
+    pub fn timestamp(&self, x: u8, z: u8) -> Option<u32> {
+        let index = location_index(x, z);
+        self.locations[index]?;
+        Some(self.timestamps[index])
+    }
+
+    /// Parses the chunk at region-relative `x`, `z`, or `None` if no chunk
+    /// is present there.
+    pub fn get(&mut self, x: u8, z: u8) -> Option<anyhow::Result<Chunk>> {
+        let location = self.locations[location_index(x, z)]?;
+        Some(self.read_chunk(location, x, z))
+    }
+
+    /// Iterates every present chunk in the region, parsing each lazily as
+    /// it's reached instead of eagerly loading the whole region up front.
+    pub fn iter(&mut self) -> RegionIter<'_, R> {
+        RegionIter {
+            region: self,
+            index: 0,
         }
     }
 
-    let mut chunks = Vec::<Chunk>::with_capacity(chunk_locations.len());
-    for location in chunk_locations {
-        reader.seek(SeekFrom::Start(location as u64 * SECTOR_SIZE as u64))?;
-        chunks.push(parse_chunk(reader)?);
+    fn read_chunk(&mut self, location: ChunkLocation, x: u8, z: u8) -> anyhow::Result<Chunk> {
+        let base = location.sector_offset as u64 * SECTOR_SIZE as u64;
+        let limit = location.sector_count as u64 * SECTOR_SIZE as u64;
+        let mut take = Take::new(&mut self.reader, base, limit)?;
+        parse_chunk(&mut take, &self.region_dir, x, z)
+    }
+}
+
+/// Advances `index` past `locations` until it finds the next present
+/// chunk's coordinates and location, for [`RegionIter`] and
+/// [`RegionIntoIter`].
+fn next_present_chunk(
+    index: &mut usize,
+    locations: &[Option<ChunkLocation>],
+) -> Option<(u8, u8, ChunkLocation)> {
+    while *index < locations.len() {
+        let i = *index;
+        *index += 1;
+        if let Some(location) = locations[i] {
+            return Some(((i % 32) as u8, (i / 32) as u8, location));
+        }
     }
+    None
+}
+
+/// Borrowing iterator over a [`RegionReader`]'s present chunks, from
+/// [`RegionReader::iter`].
+pub struct RegionIter<'a, R> {
+    region: &'a mut RegionReader<R>,
+    index: usize,
+}
+
+impl<R> Iterator for RegionIter<'_, R>
+where
+    R: Read + Seek,
+{
+    type Item = anyhow::Result<Chunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x, z, location) = next_present_chunk(&mut self.index, &self.region.locations)?;
+        Some(self.region.read_chunk(location, x, z))
+    }
+}
+
+/// Owning iterator over a [`RegionReader`]'s present chunks, from
+/// `RegionReader`'s [`IntoIterator`] impl.
+pub struct RegionIntoIter<R> {
+    region: RegionReader<R>,
+    index: usize,
+}
+
+impl<R> IntoIterator for RegionReader<R>
+where
+    R: Read + Seek,
+{
+    type Item = anyhow::Result<Chunk>;
+    type IntoIter = RegionIntoIter<R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        RegionIntoIter {
+            region: self,
+            index: 0,
+        }
+    }
+}
+
+impl<R> Iterator for RegionIntoIter<R>
+where
+    R: Read + Seek,
+{
+    type Item = anyhow::Result<Chunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x, z, location) = next_present_chunk(&mut self.index, &self.region.locations)?;
+        Some(self.region.read_chunk(location, x, z))
+    }
+}
+
+/// Serializes a chunk to binary NBT and zlib compresses it, returning the
+/// compression type byte followed by the compressed bytes as stored in an
+/// Anvil chunk entry.
+fn chunk_payload(chunk: &Chunk) -> anyhow::Result<Vec<u8>> {
+    let mut uncompressed = Vec::new();
+    to_writer(&mut uncompressed, chunk)?;
+
+    let mut compressed = Vec::new();
+    let mut encoder = ZlibEncoder::new(&mut compressed, Compression::default());
+    encoder.write_all(&uncompressed)?;
+    encoder.finish()?;
+
+    let mut payload = Vec::with_capacity(compressed.len() + 1);
+    payload.push(2); // zlib, compression type 2 in parse_chunk
+    payload.extend_from_slice(&compressed);
+    Ok(payload)
+}
+
+/// Writes a single chunk as a standalone, length-prefixed Anvil chunk entry
+/// (zlib compressed) at the writer's current position. This is the raw entry
+/// format used within a region file, not a full region.
+pub fn write_chunk<W>(writer: &mut W, chunk: &Chunk) -> anyhow::Result<()>
+where
+    W: Write,
+{
+    let payload = chunk_payload(chunk)?;
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(&payload)?;
+    Ok(())
+}
+
+/// Writes a full region file containing the given chunks. Each chunk is
+/// placed according to its own `x_pos`/`z_pos` region-relative coordinates
+/// (`x_pos mod 32`, `z_pos mod 32`), mirroring the layout [`RegionReader`]
+/// reads.
+pub fn write_region<W>(writer: &mut W, chunks: &[Chunk]) -> anyhow::Result<()>
+where
+    W: Write + Seek,
+{
+    let mut locations = [0u8; SECTOR_SIZE];
+    let timestamps = [0u8; SECTOR_SIZE];
+
+    writer.write_all(&locations)?;
+    writer.write_all(&timestamps)?;
+
+    let mut next_sector = 2u32;
+    for chunk in chunks {
+        let payload = chunk_payload(chunk)?;
+        let entry_len = 4 + payload.len();
+        let sectors = ((entry_len + SECTOR_SIZE - 1) / SECTOR_SIZE) as u32;
+
+        writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+        writer.write_all(&payload)?;
+        writer.write_all(&vec![0; sectors as usize * SECTOR_SIZE - entry_len])?;
+
+        let x = chunk.x_pos.rem_euclid(32) as usize;
+        let z = chunk.z_pos.rem_euclid(32) as usize;
+        let offset = (z * 32 + x) * 4;
+        locations[offset..offset + 3].copy_from_slice(&next_sector.to_be_bytes()[1..]);
+        locations[offset + 3] = sectors as u8;
+
+        next_sector += sectors;
+    }
+
+    writer.seek(SeekFrom::Start(0))?;
+    writer.write_all(&locations)?;
 
-    Ok(chunks)
+    Ok(())
 }
@@ -66,6 +66,34 @@ pub struct Section {
     pub sky_light: Option<ByteArray>,
 }
 
+impl Section {
+    /// Gets the block light level at the given section-relative coordinates,
+    /// or 0 if the section has no block light data.
+    pub fn block_light_at(&self, x: usize, y: usize, z: usize) -> u8 {
+        nybble_at(self.block_light.as_ref(), x, y, z).unwrap_or(0)
+    }
+
+    /// Gets the sky light level at the given section-relative coordinates, or
+    /// 15 (fully lit) if the section has no sky light data.
+    pub fn sky_light_at(&self, x: usize, y: usize, z: usize) -> u8 {
+        nybble_at(self.sky_light.as_ref(), x, y, z).unwrap_or(15)
+    }
+}
+
+/// Reads one nybble out of a packed block/sky light `ByteArray`, where each
+/// byte holds two blocks' light levels.
+fn nybble_at(light: Option<&ByteArray>, x: usize, y: usize, z: usize) -> Option<u8> {
+    let light = light?;
+    let index = y * 256 + z * 16 + x;
+    let byte = light.get(index / 2)? as u8;
+
+    Some(if index % 2 == 0 {
+        byte & 0x0f
+    } else {
+        (byte >> 4) & 0x0f
+    })
+}
+
 /// Block states are stored as a packed array of longs that represent indices
 /// into the palette. If there is only one block state in the section then the
 /// array is omitted.
@@ -75,19 +103,29 @@ pub struct BlockStates {
     pub data: Option<LongArray>,
 }
 
+/// DataVersion at which block state packing switched from the legacy
+/// straddling layout to the current layout where indices never cross a long
+/// boundary (1.16, 20w17a).
+const ALIGNED_PACKING_DATA_VERSION: i32 = 2529;
+
 impl BlockStates {
-    /// Gets the block state for a block a the given section-relative coordinates.
-    pub fn block(&self, x: usize, y: usize, z: usize) -> &BlockStatePalette {
+    /// Gets the block state for a block a the given section-relative
+    /// coordinates. `data_version` is the owning chunk's `DataVersion`, needed
+    /// to tell which packing layout the data was written with.
+    pub fn block(&self, x: usize, y: usize, z: usize, data_version: i32) -> &BlockStatePalette {
         if let Some(data) = self.data.as_ref() {
             let packed_index = y * 16 * 16 + z * 16 + x;
             let bits_per_block = self.bits_per_block();
-            let blocks_per_long = 64 / bits_per_block;
 
-            let data_index = packed_index / blocks_per_long;
-            let long_index = packed_index % blocks_per_long;
-            let palette_index = (data.get(data_index).unwrap() as u64
-                >> (long_index * bits_per_block))
-                & ((1 << bits_per_block) - 1);
+            let palette_index = if data_version < ALIGNED_PACKING_DATA_VERSION {
+                straddling_index(data, packed_index, bits_per_block)
+            } else {
+                let blocks_per_long = 64 / bits_per_block;
+                let data_index = packed_index / blocks_per_long;
+                let long_index = packed_index % blocks_per_long;
+                (data.get(data_index).unwrap() as u64 >> (long_index * bits_per_block))
+                    & ((1 << bits_per_block) - 1)
+            };
 
             &self.palette[palette_index as usize]
         } else {
@@ -96,32 +134,110 @@ impl BlockStates {
     }
 
     /// Unpacks the block state data into a flat array of block state indices.
-    pub fn unpack_data(&mut self) -> Vec<u16> {
+    /// `data_version` is the owning chunk's `DataVersion`, needed to tell
+    /// which packing layout the data was written with.
+    pub fn unpack_data(&mut self, data_version: i32) -> Vec<u16> {
         let bits_per_block = self.bits_per_block();
-        if let Some(data) = self.data.as_mut() {
-            if self.palette.len() <= 32 {
-                // Assumes little endian
-                if self.palette.len() <= 16 {
-                    return unpack::unpack4(data.as_raw_slice(), data.big_endian());
-                }
-                return unpack::unpack5(data.as_raw_slice(), data.big_endian());
-            }
-
-            let blocks_per_long = 64 / bits_per_block;
-            let mut unpacked = Vec::with_capacity(data.len() * blocks_per_long);
-            for long in data.as_slice() {
-                for i in 0..blocks_per_long {
-                    unpacked.push(
-                        ((long >> (i * bits_per_block)) & ((1 << bits_per_block) - 1)) as u16,
-                    );
-                }
-            }
+        if let Some(data) = self.data.as_ref() {
+            let layout = if data_version < ALIGNED_PACKING_DATA_VERSION {
+                unpack::Layout::Spanning
+            } else {
+                unpack::Layout::Aligned
+            };
+
+            let mut unpacked = unpack::unpack(
+                data.as_raw_slice(),
+                bits_per_block,
+                data.big_endian(),
+                layout,
+            );
+            unpacked.truncate(16 * 16 * 16);
             unpacked
         } else {
             vec![0; 16 * 16 * 16]
         }
     }
 
+    /// Packs a flat array of block state indices into the block state data,
+    /// the inverse of [`BlockStates::unpack_data`], for writing a section
+    /// back out after its blocks have been edited. `indices` is padded with
+    /// trailing zeros up to a whole number of longs if needed. `data_version`
+    /// is the owning chunk's `DataVersion`, needed to tell which packing
+    /// layout to write.
+    pub fn pack_data(&mut self, indices: &[u16], data_version: i32) {
+        let bits_per_block = self.bits_per_block();
+        let layout = if data_version < ALIGNED_PACKING_DATA_VERSION {
+            unpack::Layout::Spanning
+        } else {
+            unpack::Layout::Aligned
+        };
+
+        self.data = Some(LongArray::new(unpack::pack(
+            &pad_to_long_multiple(indices, bits_per_block),
+            bits_per_block,
+            true,
+            layout,
+        )));
+    }
+
+    /// Unpacks the block state data the same way as [`BlockStates::unpack_data`],
+    /// but checks every index against `palette`'s bounds instead of trusting
+    /// the packed data, for callers that can't tolerate a panic indexing
+    /// `palette` with a corrupt or malicious index.
+    pub fn try_unpack_data(&mut self, data_version: i32) -> Result<Vec<u16>, unpack::UnpackError> {
+        let bits_per_block = self.bits_per_block();
+        if let Some(data) = self.data.as_ref() {
+            let layout = if data_version < ALIGNED_PACKING_DATA_VERSION {
+                unpack::Layout::Spanning
+            } else {
+                unpack::Layout::Aligned
+            };
+
+            unpack::unpack_validated(
+                data.as_raw_slice(),
+                bits_per_block,
+                data.big_endian(),
+                layout,
+                16 * 16 * 16,
+                self.palette.len() as u16,
+            )
+        } else {
+            Ok(vec![0; 16 * 16 * 16])
+        }
+    }
+
+    /// Tallies how many blocks have each palette index, fusing the unpack and
+    /// count passes into one instead of unpacking the full section and then
+    /// counting occurrences in a second pass. Returns a vector the same
+    /// length as `palette`. `data_version` is the owning chunk's
+    /// `DataVersion`, needed to tell which packing layout the data was
+    /// written with.
+    pub fn distribution(&mut self, data_version: i32) -> Vec<u64> {
+        let bits_per_block = self.bits_per_block();
+        if let Some(data) = self.data.as_ref() {
+            let layout = if data_version < ALIGNED_PACKING_DATA_VERSION {
+                unpack::Layout::Spanning
+            } else {
+                unpack::Layout::Aligned
+            };
+
+            let (_, counts) = unpack::unpack_and_histogram(
+                data.as_raw_slice(),
+                bits_per_block,
+                data.big_endian(),
+                layout,
+                16 * 16 * 16,
+            );
+
+            counts[..self.palette.len()]
+                .iter()
+                .map(|&count| count as u64)
+                .collect()
+        } else {
+            vec![16 * 16 * 16]
+        }
+    }
+
     /// The number of bits used to store the block state indices. Minimum of 4
     /// and maximum of 12 since palette length is limited to 4096.
     fn bits_per_block(&self) -> usize {
@@ -130,6 +246,38 @@ impl BlockStates {
     }
 }
 
+/// Pads `indices` with trailing zeros up to a whole number of `bits`-wide
+/// values per long, since [`unpack::pack`]'s aligned layout (`pack4`/`pack5`/
+/// `packn`) requires its input length to already be a multiple of that.
+fn pad_to_long_multiple(indices: &[u16], bits: usize) -> Vec<u16> {
+    let values_per_long = 64 / bits;
+    let remainder = indices.len() % values_per_long;
+
+    let mut padded = indices.to_vec();
+    if remainder != 0 {
+        padded.resize(padded.len() + (values_per_long - remainder), 0);
+    }
+    padded
+}
+
+/// Reads the `bits_per_block`-wide index at `packed_index` from the legacy
+/// (pre-1.16) straddling layout, where indices are packed tightly with no
+/// regard for long boundaries and so may span two consecutive longs.
+fn straddling_index(data: &LongArray, packed_index: usize, bits_per_block: usize) -> u64 {
+    let bit_offset = packed_index * bits_per_block;
+    let long_index = bit_offset / 64;
+    let shift = bit_offset % 64;
+    let mask = (1u64 << bits_per_block) - 1;
+
+    let low = data.get(long_index).unwrap() as u64 >> shift;
+    if shift + bits_per_block > 64 {
+        let high = data.get(long_index + 1).unwrap() as u64;
+        (low | (high << (64 - shift))) & mask
+    } else {
+        low & mask
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockStatePalette {
     #[serde(rename = "Name")]
@@ -139,6 +287,31 @@ pub struct BlockStatePalette {
     pub properties: Option<HashMap<String, String>>,
 }
 
+impl BlockStatePalette {
+    /// A canonical string rendering of the full block state, e.g.
+    /// `minecraft:oak_log[axis=x,waterlogged=true]`. Properties are sorted by
+    /// name so that identical states always render to the same string
+    /// regardless of the NBT map's iteration order.
+    pub fn state_string(&self) -> String {
+        let Some(properties) = &self.properties else {
+            return self.name.clone();
+        };
+        if properties.is_empty() {
+            return self.name.clone();
+        }
+
+        let mut properties: Vec<(&String, &String)> = properties.iter().collect();
+        properties.sort_unstable_by_key(|(name, _)| *name);
+
+        let rendered = properties
+            .into_iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{}[{}]", self.name, rendered)
+    }
+}
+
 /// Biomes are stored as a packed array of longs that represent indices into the
 /// palette. If there is only one biome in the section then the array is
 /// omitted.
@@ -148,6 +321,86 @@ pub struct Biomes {
     pub data: Option<LongArray>,
 }
 
+impl Biomes {
+    /// Gets the biome for a cell at the given section-relative biome
+    /// coordinates, each in `0..4`. Biomes are stored at a quarter of block
+    /// resolution, so a single biome covers a 4x4x4 block volume.
+    pub fn biome(&self, x: usize, y: usize, z: usize) -> &str {
+        if let Some(data) = self.data.as_ref() {
+            let packed_index = y * 16 + z * 4 + x;
+            let bits_per_biome = self.bits_per_biome();
+            let cells_per_long = 64 / bits_per_biome;
+
+            let data_index = packed_index / cells_per_long;
+            let cell_index = packed_index % cells_per_long;
+            let palette_index = (data.get(data_index).unwrap() as u64
+                >> (cell_index * bits_per_biome))
+                & ((1 << bits_per_biome) - 1);
+
+            &self.palette[palette_index as usize]
+        } else {
+            &self.palette[0]
+        }
+    }
+
+    /// Unpacks the biome data into a flat array of palette indices, one per
+    /// cell of the 4x4x4 biome grid.
+    pub fn unpack_data(&mut self) -> Vec<u16> {
+        let bits_per_biome = self.bits_per_biome();
+        if let Some(data) = self.data.as_ref() {
+            let mut unpacked =
+                unpack::unpackn(data.as_raw_slice(), bits_per_biome, data.big_endian());
+            unpacked.truncate(4 * 4 * 4);
+            unpacked
+        } else {
+            vec![0; 4 * 4 * 4]
+        }
+    }
+
+    /// Unpacks the biome data and maps each index through `palette` in one
+    /// pass, for callers that want resolved biome names instead of raw
+    /// indices and don't want to hold the intermediate index `Vec`
+    /// themselves.
+    pub fn biomes(&mut self) -> Vec<String> {
+        let bits_per_biome = self.bits_per_biome();
+        if let Some(data) = self.data.as_ref() {
+            let mut biomes = unpack::unpack_and_remap(
+                data.as_raw_slice(),
+                bits_per_biome,
+                data.big_endian(),
+                unpack::Layout::Aligned,
+                &self.palette,
+            );
+            biomes.truncate(4 * 4 * 4);
+            biomes
+        } else {
+            vec![self.palette[0].clone(); 4 * 4 * 4]
+        }
+    }
+
+    /// Packs a flat array of biome palette indices into the biome data, the
+    /// inverse of [`Biomes::unpack_data`], for writing a section back out
+    /// after its biomes have been edited. `indices` is padded with trailing
+    /// zeros up to a whole number of longs if needed.
+    pub fn pack_data(&mut self, indices: &[u16]) {
+        let bits_per_biome = self.bits_per_biome();
+
+        self.data = Some(LongArray::new(unpack::packn(
+            &pad_to_long_multiple(indices, bits_per_biome),
+            bits_per_biome,
+            true,
+        )));
+    }
+
+    /// The number of bits used to store the biome indices. Unlike
+    /// [`BlockStates::bits_per_block`], there's no floor of 4 bits since the
+    /// biome palette is much smaller than the block palette.
+    fn bits_per_biome(&self) -> usize {
+        // Equivalent to ceil(log_2(palette.len())), with a minimum of 1.
+        (usize::BITS - (self.palette.len() - 1).leading_zeros()).max(1) as usize
+    }
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct BlockEntity {
     pub id: String,
@@ -162,7 +415,10 @@ pub struct BlockEntity {
 }
 
 /// Height maps are stored as a map of name to array of longs. The arrays each
-/// hold 256 9-bit values packed into longs.
+/// hold 256 9-bit values packed into longs, indexed by `z * 16 + x`, with no
+/// value crossing a long boundary (7 values per long, using 63 of the 64
+/// bits). Decoded values are a Y offset from the chunk's bottom section, not
+/// an absolute world height; add the world floor to get the real world Y.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub struct HeightMaps {
@@ -174,6 +430,49 @@ pub struct HeightMaps {
     pub world_surface_wg: LongArray,
 }
 
+fn height_map_at(data: &LongArray, x: usize, z: usize) -> i32 {
+    let index = z * 16 + x;
+    let long = data.get(index / 7).unwrap();
+    ((long >> ((index % 7) * 9)) & 0x1ff) as i32
+}
+
+fn decode_height_map(data: &LongArray) -> [i32; 256] {
+    let mut heights = [0; 256];
+    for (i, height) in heights.iter_mut().enumerate() {
+        let long = data.get(i / 7).unwrap();
+        *height = ((long >> ((i % 7) * 9)) & 0x1ff) as i32;
+    }
+    heights
+}
+
+macro_rules! impl_height_map_accessors {
+    ($($field:ident => $at:ident, $decode:ident;)*) => {
+        impl HeightMaps {
+            $(
+                /// Gets the packed height at the given chunk-relative x and z
+                /// coordinates, each in `0..16`.
+                pub fn $at(&self, x: usize, z: usize) -> i32 {
+                    height_map_at(&self.$field, x, z)
+                }
+
+                /// Decodes all 256 packed heights, indexed by `z * 16 + x`.
+                pub fn $decode(&self) -> [i32; 256] {
+                    decode_height_map(&self.$field)
+                }
+            )*
+        }
+    };
+}
+
+impl_height_map_accessors! {
+    motion_blocking => motion_blocking_at, decode_motion_blocking;
+    motion_blocking_no_leaves => motion_blocking_no_leaves_at, decode_motion_blocking_no_leaves;
+    ocean_floor => ocean_floor_at, decode_ocean_floor;
+    ocean_floor_wg => ocean_floor_wg_at, decode_ocean_floor_wg;
+    world_surface => world_surface_at, decode_world_surface;
+    world_surface_wg => world_surface_wg_at, decode_world_surface_wg;
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct BlendingData {
     pub min_section: i32,
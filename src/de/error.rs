@@ -15,6 +15,10 @@ pub enum Error {
     InvalidTagForSeq(TagType),
     InvalidTagType(u8),
     InvalidTagForBytes(TagType),
+    RecursionLimitExceeded,
+    TrailingData,
+    InvalidVarint,
+    LimitExceeded,
 }
 
 impl From<std::io::Error> for Error {
@@ -51,6 +55,15 @@ impl std::fmt::Display for Error {
             Error::InvalidTagForBytes(tag_type) => {
                 write!(f, "invalid tag type for byte array: {:?}", tag_type)
             }
+            Error::RecursionLimitExceeded => write!(
+                f,
+                "recursion limit exceeded: compounds/lists are nested too deeply"
+            ),
+            Error::TrailingData => write!(f, "trailing data after the root compound/list"),
+            Error::InvalidVarint => write!(f, "invalid or overlong varint"),
+            Error::LimitExceeded => {
+                write!(f, "declared length exceeds the configured allocation limit")
+            }
         }
     }
 }
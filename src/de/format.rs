@@ -0,0 +1,296 @@
+use super::{
+    read::{Read, Reference},
+    Error,
+};
+
+/// Per-platform NBT wire encoding, parameterizing [`super::Deserializer`]
+/// analogous to how `ser::formatter::Formatter` parameterizes
+/// `ser::Serializer`. Unlike `Formatter`, reading needs no per-call state:
+/// the root tag header is consumed once in `deserialize_any` rather than on
+/// every nesting level, so every `Format` implementor below is a zero-sized
+/// marker and every method is a plain associated function.
+pub(super) trait Format {
+    /// Whether fixed-width multi-byte fields are big-endian on the wire.
+    /// `IntArray`/`LongArray` store their payload internally as big-endian
+    /// bytes regardless of wire format (matching `ser::mod`'s
+    /// `serialize_bytes` and the Java wire format), so the deserializer
+    /// consults this to normalize non-big-endian wire payloads on the way
+    /// in; see [`super::Deserializer::read_array_bytes`].
+    const IS_BIG_ENDIAN: bool;
+
+    fn read_byte<'de, R>(reader: &mut R) -> Result<i8, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(reader.read_raw::<1>()?[0] as i8)
+    }
+
+    fn read_short<'de, R>(reader: &mut R) -> Result<i16, Error>
+    where
+        R: Read<'de>;
+    fn read_int<'de, R>(reader: &mut R) -> Result<i32, Error>
+    where
+        R: Read<'de>;
+    fn read_long<'de, R>(reader: &mut R) -> Result<i64, Error>
+    where
+        R: Read<'de>;
+    fn read_float<'de, R>(reader: &mut R) -> Result<f32, Error>
+    where
+        R: Read<'de>;
+    fn read_double<'de, R>(reader: &mut R) -> Result<f64, Error>
+    where
+        R: Read<'de>;
+
+    /// Length in bytes of the next MUTF-8 string's payload.
+    fn read_string_len<'de, R>(reader: &mut R) -> Result<usize, Error>
+    where
+        R: Read<'de>;
+
+    fn read_string<'de, 's, R>(
+        reader: &'s mut R,
+        scratch: &'s mut Vec<u8>,
+    ) -> Result<Reference<'de, 's, str>, Error>
+    where
+        R: Read<'de>,
+    {
+        let len = Self::read_string_len(reader)?;
+        reader.read_string_with_len(len, scratch)
+    }
+
+    fn ignore_string<'de, R>(reader: &mut R) -> Result<(), Error>
+    where
+        R: Read<'de>,
+    {
+        let len = Self::read_string_len(reader)?;
+        reader.skip_raw(len)
+    }
+}
+
+/// Java Edition's big-endian binary NBT: the default wire format, and the
+/// only one this crate supported before Bedrock Edition support was added.
+/// Mirrors `ser::formatter::BinaryFormatter`.
+pub(super) struct BinaryFormat;
+
+impl BinaryFormat {
+    pub(super) fn new() -> Self {
+        BinaryFormat
+    }
+}
+
+impl Format for BinaryFormat {
+    const IS_BIG_ENDIAN: bool = true;
+
+    fn read_short<'de, R>(reader: &mut R) -> Result<i16, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(i16::from_be_bytes(reader.read_raw::<2>()?))
+    }
+
+    fn read_int<'de, R>(reader: &mut R) -> Result<i32, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(i32::from_be_bytes(reader.read_raw::<4>()?))
+    }
+
+    fn read_long<'de, R>(reader: &mut R) -> Result<i64, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(i64::from_be_bytes(reader.read_raw::<8>()?))
+    }
+
+    fn read_float<'de, R>(reader: &mut R) -> Result<f32, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(f32::from_be_bytes(reader.read_raw::<4>()?))
+    }
+
+    fn read_double<'de, R>(reader: &mut R) -> Result<f64, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(f64::from_be_bytes(reader.read_raw::<8>()?))
+    }
+
+    fn read_string_len<'de, R>(reader: &mut R) -> Result<usize, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(u16::from_be_bytes(reader.read_raw::<2>()?) as usize)
+    }
+}
+
+/// Bedrock Edition's little-endian disk NBT format, used by world and entity
+/// save files: structurally identical to [`BinaryFormat`], but every
+/// multi-byte field, including the string length prefix, is little-endian
+/// instead of big-endian. Mirrors `ser::formatter::LittleEndianFormatter`.
+pub(super) struct LittleEndianFormat;
+
+impl LittleEndianFormat {
+    pub(super) fn new() -> Self {
+        LittleEndianFormat
+    }
+}
+
+impl Format for LittleEndianFormat {
+    const IS_BIG_ENDIAN: bool = false;
+
+    fn read_short<'de, R>(reader: &mut R) -> Result<i16, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(i16::from_le_bytes(reader.read_raw::<2>()?))
+    }
+
+    fn read_int<'de, R>(reader: &mut R) -> Result<i32, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(i32::from_le_bytes(reader.read_raw::<4>()?))
+    }
+
+    fn read_long<'de, R>(reader: &mut R) -> Result<i64, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(i64::from_le_bytes(reader.read_raw::<8>()?))
+    }
+
+    fn read_float<'de, R>(reader: &mut R) -> Result<f32, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(f32::from_le_bytes(reader.read_raw::<4>()?))
+    }
+
+    fn read_double<'de, R>(reader: &mut R) -> Result<f64, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(f64::from_le_bytes(reader.read_raw::<8>()?))
+    }
+
+    fn read_string_len<'de, R>(reader: &mut R) -> Result<usize, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(u16::from_le_bytes(reader.read_raw::<2>()?) as usize)
+    }
+}
+
+/// Bedrock Edition's network NBT format, used to receive NBT sent over the
+/// Bedrock protocol: the same shape as [`LittleEndianFormat`], but `int` and
+/// `long` payloads and the string length prefix are varints — `int` a ZigZag
+/// varint, matching the list/array length fields since they're encoded the
+/// same way, `long` a ZigZag varlong, and the string length an unsigned
+/// varint of the MUTF-8 payload's byte count — while `byte`/`short`/`float`/`double`
+/// stay fixed-width little-endian. Mirrors `ser::formatter::NetworkFormatter`.
+pub(super) struct NetworkFormat;
+
+impl NetworkFormat {
+    pub(super) fn new() -> Self {
+        NetworkFormat
+    }
+}
+
+impl Format for NetworkFormat {
+    const IS_BIG_ENDIAN: bool = false;
+
+    fn read_short<'de, R>(reader: &mut R) -> Result<i16, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(i16::from_le_bytes(reader.read_raw::<2>()?))
+    }
+
+    fn read_int<'de, R>(reader: &mut R) -> Result<i32, Error>
+    where
+        R: Read<'de>,
+    {
+        read_zigzag_varint(reader)
+    }
+
+    fn read_long<'de, R>(reader: &mut R) -> Result<i64, Error>
+    where
+        R: Read<'de>,
+    {
+        read_zigzag_varlong(reader)
+    }
+
+    fn read_float<'de, R>(reader: &mut R) -> Result<f32, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(f32::from_le_bytes(reader.read_raw::<4>()?))
+    }
+
+    fn read_double<'de, R>(reader: &mut R) -> Result<f64, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(f64::from_le_bytes(reader.read_raw::<8>()?))
+    }
+
+    fn read_string_len<'de, R>(reader: &mut R) -> Result<usize, Error>
+    where
+        R: Read<'de>,
+    {
+        Ok(read_unsigned_varint(reader)? as usize)
+    }
+}
+
+/// Reads an unsigned variable-length integer: 7 bits of value per byte,
+/// least significant group first, with the high bit of each byte set except
+/// on the last one. Mirrors `ser::formatter::write_unsigned_varint`.
+fn read_unsigned_varint<'de, R>(reader: &mut R) -> Result<u32, Error>
+where
+    R: Read<'de>,
+{
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let byte = reader.read_raw::<1>()?[0];
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(Error::InvalidVarint)
+}
+
+/// Same as [`read_unsigned_varint`], but for 64-bit values.
+fn read_unsigned_varlong<'de, R>(reader: &mut R) -> Result<u64, Error>
+where
+    R: Read<'de>,
+{
+    let mut value = 0u64;
+    for shift in (0..70).step_by(7) {
+        let byte = reader.read_raw::<1>()?[0];
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(Error::InvalidVarint)
+}
+
+/// Reads a ZigZag-encoded [`read_unsigned_varint`] back into a signed value.
+/// Mirrors `ser::formatter::write_zigzag_varint`.
+fn read_zigzag_varint<'de, R>(reader: &mut R) -> Result<i32, Error>
+where
+    R: Read<'de>,
+{
+    let value = read_unsigned_varint(reader)?;
+    Ok(((value >> 1) as i32) ^ -((value & 1) as i32))
+}
+
+/// Same as [`read_zigzag_varint`], but for 64-bit values.
+fn read_zigzag_varlong<'de, R>(reader: &mut R) -> Result<i64, Error>
+where
+    R: Read<'de>,
+{
+    let value = read_unsigned_varlong(reader)?;
+    Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+}
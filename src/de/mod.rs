@@ -5,25 +5,197 @@ use serde::{
 
 use crate::nbt::TagType;
 
-use self::{error::Error, read::Reference};
+use self::{
+    error::Error,
+    format::{BinaryFormat, Format, LittleEndianFormat, NetworkFormat},
+    read::Reference,
+};
 
 mod error;
+mod format;
 mod read;
+mod value;
+
+pub use value::from_tag;
+
+/// Maximum nesting depth of compounds/lists used by [`from_reader`] and
+/// [`from_slice`]. Override it with [`from_reader_with_config`]/
+/// [`from_slice_with_config`] for files trusted to nest deeper (or
+/// untrusted enough to warrant an even smaller limit). Kept well under what
+/// this recursive-descent deserializer can actually survive on a debug-build
+/// thread stack (matching the 128 serde_json itself defaults to), so the
+/// counter trips with `Error::RecursionLimitExceeded` before the real stack
+/// overflows — the whole point of having a depth limit.
+const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Maximum number of bytes/elements a single declared length (a `List`
+/// entry count, or a string's or `ByteArray`/`IntArray`/`LongArray`'s byte
+/// length) may have, by default. A bigger declared length is rejected with
+/// `Error::LimitExceeded` as soon as it's read, so a hostile blob claiming
+/// a huge length fails fast rather than running the allocator out of
+/// memory before any of the claimed data has even been read.
+const DEFAULT_MAX_ALLOC: usize = 64 * 1024 * 1024;
+
+/// Options controlling deserialization limits. Build one with
+/// [`Config::new`] and chain the setters, mirroring `ser::Config`.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    max_depth: usize,
+    max_alloc: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_alloc: DEFAULT_MAX_ALLOC,
+        }
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-struct Deserializer<R> {
+    /// Sets the maximum nesting depth of compounds/lists. Exceeding it
+    /// returns `Error::RecursionLimitExceeded` instead of recursing further,
+    /// so a maliciously deep file can't blow the stack.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum size, in bytes, of a single declared length (a
+    /// `List` entry count or an array's byte length) that may be
+    /// pre-allocated/reserved for before any of that data has actually been
+    /// read. Exceeding it returns `Error::LimitExceeded` instead of trusting
+    /// the declared length, so a small hostile blob can't claim a huge
+    /// length to force an oversized allocation.
+    pub fn max_alloc(mut self, max_alloc: usize) -> Self {
+        self.max_alloc = max_alloc;
+        self
+    }
+}
+
+/// `F` selects the wire encoding (Java big-endian, Bedrock little-endian,
+/// Bedrock network varint, ...) via [`format::Format`], mirroring
+/// `ser::Serializer<W, F = BinaryFormatter>`. Unlike `Formatter`, a `Format`
+/// carries no per-call state, so `F` is phantom here rather than a stored
+/// field.
+struct Deserializer<R, F = BinaryFormat> {
     reader: R,
     scratch: Vec<u8>,
+    // Remaining compound/list nesting budget: decremented on entering a
+    // nested compound/list and restored on leaving it, so sibling entries at
+    // the same depth don't exhaust it.
+    depth_remaining: usize,
+    // Ceiling on a single declared length's eager allocation; see
+    // `Config::max_alloc`. Unlike `depth_remaining`, this isn't a shared,
+    // depleting budget: it's re-applied in full to every individual
+    // List/array length.
+    max_alloc: usize,
+    format: std::marker::PhantomData<F>,
 }
 
-impl<'de, R> Deserializer<R>
+impl<'de, R, F> Deserializer<R, F>
 where
     R: read::Read<'de>,
+    F: Format,
 {
-    fn new(reader: R) -> Self {
+    fn with_config(reader: R, config: Config) -> Self {
         Deserializer {
             reader,
             scratch: Vec::new(),
+            depth_remaining: config.max_depth,
+            max_alloc: config.max_alloc,
+            format: std::marker::PhantomData,
+        }
+    }
+
+    /// Rejects a just-read declared length (a `List` entry count or an
+    /// array's byte length) that exceeds `max_alloc`, so that length is
+    /// never used to eagerly allocate/reserve before any of the data it
+    /// describes has actually been read.
+    fn check_alloc_len(&self, len: usize) -> Result<(), Error> {
+        if len > self.max_alloc {
+            Err(Error::LimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Same as [`Format::read_string`], but goes through
+    /// [`Deserializer::check_alloc_len`] between reading the declared length
+    /// and allocating/reading the payload.
+    fn read_string(&mut self) -> Result<Reference<'de, '_, str>, Error> {
+        let len = F::read_string_len(&mut self.reader)?;
+        self.check_alloc_len(len)?;
+        self.reader.read_string_with_len(len, &mut self.scratch)
+    }
+
+    fn enter_nesting(&mut self) -> Result<(), Error> {
+        match self.depth_remaining.checked_sub(1) {
+            Some(remaining) => {
+                self.depth_remaining = remaining;
+                Ok(())
+            }
+            None => Err(Error::RecursionLimitExceeded),
+        }
+    }
+
+    fn exit_nesting(&mut self) {
+        self.depth_remaining += 1;
+    }
+
+    /// Confirms no bytes remain after the value just deserialized, mirroring
+    /// serde_cbor's/serde_wormhole's `Deserializer::end`.
+    fn end(&mut self) -> Result<(), Error> {
+        if self.reader.is_at_eof()? {
+            Ok(())
+        } else {
+            Err(Error::TrailingData)
+        }
+    }
+
+    /// Reads an NBT array payload (`ByteArray`/`IntArray`/`LongArray`): an
+    /// `F`-encoded `Int` length, followed by `len * len_multiplier` raw
+    /// bytes.
+    ///
+    /// `nbt::IntArray`/`nbt::LongArray` always store their payload as
+    /// big-endian bytes, matching the Java wire format and
+    /// `ser::serialize_bytes`, and only track separately whether that
+    /// happens to already match the host's endianness. Bedrock's
+    /// little-endian and network formats encode array elements
+    /// little-endian on the wire instead, so those need normalizing to
+    /// big-endian here to preserve that invariant.
+    fn read_array_bytes(
+        &mut self,
+        len_multiplier: usize,
+    ) -> Result<Reference<'de, '_, [u8]>, Error> {
+        let len =
+            usize::try_from(F::read_int(&mut self.reader)?).map_err(|_| Error::NegativeLength)?;
+        self.check_alloc_len(len.saturating_mul(len_multiplier))?;
+
+        if F::IS_BIG_ENDIAN || len_multiplier == 1 {
+            return self
+                .reader
+                .read_bytes(len * len_multiplier, &mut self.scratch);
         }
+
+        let bytes = match self
+            .reader
+            .read_bytes(len * len_multiplier, &mut self.scratch)?
+        {
+            Reference::Borrowed(bytes) => bytes,
+            Reference::Copied(bytes) => bytes,
+        };
+        self.scratch = match len_multiplier {
+            4 => crate::unpack::swap_endianness_32bit(bytes),
+            8 => crate::unpack::swap_endianness_64bit(bytes),
+            _ => unreachable!("array elements are always 1, 4, or 8 bytes wide"),
+        };
+        Ok(Reference::Copied(&self.scratch))
     }
 }
 
@@ -32,23 +204,176 @@ where
     R: std::io::Read,
     T: DeserializeOwned,
 {
-    let mut de = Deserializer::new(read::Reader::new(reader));
+    from_reader_with_config(reader, Config::default())
+}
+
+pub fn from_slice<'a, T>(slice: &'a [u8]) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_slice_with_config(slice, Config::default())
+}
+
+/// Same as [`from_reader`], but with explicit [`Config`] for e.g. a
+/// non-default recursion depth limit.
+pub fn from_reader_with_config<R, T>(reader: R, config: Config) -> Result<T, Error>
+where
+    R: std::io::Read,
+    T: DeserializeOwned,
+{
+    let mut de = Deserializer::<_, BinaryFormat>::with_config(read::Reader::new(reader), config);
     let value = Deserialize::deserialize(&mut de)?;
+    de.end()?;
     Ok(value)
 }
 
-pub fn from_slice<'a, T>(slice: &'a [u8]) -> Result<T, Error>
+/// Same as [`from_slice`], but with explicit [`Config`] for e.g. a
+/// non-default recursion depth limit.
+pub fn from_slice_with_config<'a, T>(slice: &'a [u8], config: Config) -> Result<T, Error>
 where
     T: Deserialize<'a>,
 {
-    let mut de = Deserializer::new(read::Slice::new(slice));
+    let mut de = Deserializer::<_, BinaryFormat>::with_config(read::Slice::new(slice), config);
     let value = Deserialize::deserialize(&mut de)?;
+    de.end()?;
     Ok(value)
 }
 
-impl<'de, 'a, R> de::Deserializer<'de> for &'a mut Deserializer<R>
+/// Same as [`from_slice`], but returns whatever bytes remain after the root
+/// value instead of erroring on them, for parsing concatenated NBT documents
+/// out of one buffer.
+pub fn from_slice_with_trailing<'a, T>(slice: &'a [u8]) -> Result<(T, &'a [u8]), Error>
+where
+    T: Deserialize<'a>,
+{
+    let mut de =
+        Deserializer::<_, BinaryFormat>::with_config(read::Slice::new(slice), Config::default());
+    let value = Deserialize::deserialize(&mut de)?;
+    Ok((value, de.reader.remaining()))
+}
+
+/// Same as [`from_reader`], but for Bedrock Edition's little-endian disk NBT
+/// format, used by world and entity save files.
+pub fn from_reader_le<R, T>(reader: R) -> Result<T, Error>
+where
+    R: std::io::Read,
+    T: DeserializeOwned,
+{
+    from_reader_le_with_config(reader, Config::default())
+}
+
+/// Same as [`from_reader_le`], but with explicit [`Config`].
+pub fn from_reader_le_with_config<R, T>(reader: R, config: Config) -> Result<T, Error>
+where
+    R: std::io::Read,
+    T: DeserializeOwned,
+{
+    let mut de =
+        Deserializer::<_, LittleEndianFormat>::with_config(read::Reader::new(reader), config);
+    let value = Deserialize::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+/// Same as [`from_slice`], but for Bedrock Edition's little-endian disk NBT
+/// format, used by world and entity save files.
+pub fn from_slice_le<'a, T>(slice: &'a [u8]) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_slice_le_with_config(slice, Config::default())
+}
+
+/// Same as [`from_slice_le`], but with explicit [`Config`].
+pub fn from_slice_le_with_config<'a, T>(slice: &'a [u8], config: Config) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let mut de =
+        Deserializer::<_, LittleEndianFormat>::with_config(read::Slice::new(slice), config);
+    let value = Deserialize::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+/// Same as [`from_reader`], but for Bedrock Edition's network NBT format,
+/// used to receive NBT sent over the Bedrock protocol.
+pub fn from_reader_varint<R, T>(reader: R) -> Result<T, Error>
+where
+    R: std::io::Read,
+    T: DeserializeOwned,
+{
+    from_reader_varint_with_config(reader, Config::default())
+}
+
+/// Same as [`from_reader_varint`], but with explicit [`Config`].
+pub fn from_reader_varint_with_config<R, T>(reader: R, config: Config) -> Result<T, Error>
+where
+    R: std::io::Read,
+    T: DeserializeOwned,
+{
+    let mut de = Deserializer::<_, NetworkFormat>::with_config(read::Reader::new(reader), config);
+    let value = Deserialize::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+/// Same as [`from_slice`], but for Bedrock Edition's network NBT format,
+/// used to receive NBT sent over the Bedrock protocol.
+pub fn from_slice_varint<'a, T>(slice: &'a [u8]) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_slice_varint_with_config(slice, Config::default())
+}
+
+/// Same as [`from_slice_varint`], but with explicit [`Config`].
+pub fn from_slice_varint_with_config<'a, T>(slice: &'a [u8], config: Config) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let mut de = Deserializer::<_, NetworkFormat>::with_config(read::Slice::new(slice), config);
+    let value = Deserialize::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+/// Parses a Bedrock Edition `level.dat` file. Ahead of the little-endian NBT
+/// payload itself, these files have an 8-byte header: a little-endian `i32`
+/// format/version word, then a little-endian `u32` payload length, both
+/// written by the game client rather than appearing on the network.
+pub fn from_slice_bedrock_level_dat<'a, T>(slice: &'a [u8]) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_slice_bedrock_level_dat_with_config(slice, Config::default())
+}
+
+/// Same as [`from_slice_bedrock_level_dat`], but with explicit [`Config`].
+pub fn from_slice_bedrock_level_dat_with_config<'a, T>(
+    slice: &'a [u8],
+    config: Config,
+) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let header: [u8; 8] = slice
+        .get(..8)
+        .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?
+        .try_into()
+        .unwrap();
+    let payload_len = u32::from_le_bytes(header[4..8].try_into().unwrap()) as usize;
+    let payload = slice
+        .get(8..8 + payload_len)
+        .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?;
+
+    from_slice_le_with_config(payload, config)
+}
+
+impl<'de, 'a, R, F> de::Deserializer<'de> for &'a mut Deserializer<R, F>
 where
     R: read::Read<'de>,
+    F: Format,
 {
     type Error = Error;
 
@@ -61,12 +386,12 @@ where
         V: Visitor<'de>,
     {
         let tag_type = self.reader.read_tag_type()?;
-        self.reader.ignore_string()?;
+        F::ignore_string(&mut self.reader)?;
 
         match tag_type {
             TagType::List => {
                 let element_type = self.reader.read_tag_type()?;
-                let len = self.reader.read_int()?;
+                let len = F::read_int(&mut self.reader)?;
                 visitor.visit_seq(SeqAccess::new(
                     self,
                     element_type,
@@ -79,43 +404,60 @@ where
     }
 }
 
-struct UnnamedDeserializer<'a, R> {
-    de: &'a mut Deserializer<R>,
+struct UnnamedDeserializer<'a, R, F> {
+    de: &'a mut Deserializer<R, F>,
     tag_type: TagType,
 }
 
-impl<'de, 'a, R> UnnamedDeserializer<'a, R>
+impl<'de, 'a, R, F> UnnamedDeserializer<'a, R, F>
 where
     R: read::Read<'de>,
+    F: Format,
 {
-    fn new(de: &'a mut Deserializer<R>, tag_type: TagType) -> Self {
+    fn new(de: &'a mut Deserializer<R, F>, tag_type: TagType) -> Self {
         UnnamedDeserializer { de, tag_type }
     }
 }
 
-impl<'de, 'a, R> de::Deserializer<'de> for UnnamedDeserializer<'a, R>
+impl<'de, 'a, R, F> de::Deserializer<'de> for UnnamedDeserializer<'a, R, F>
 where
     R: read::Read<'de>,
+    F: Format,
 {
     type Error = Error;
 
-    forward_to_deserialize_any! { i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf unit unit_struct newtype_struct tuple tuple_struct struct enum identifier ignored_any }
+    forward_to_deserialize_any! { i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string unit unit_struct newtype_struct tuple tuple_struct struct enum identifier ignored_any }
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
         match self.tag_type {
-            TagType::Byte => visitor.visit_i8(self.de.reader.read_byte()?),
-            TagType::Short => visitor.visit_i16(self.de.reader.read_short()?),
-            TagType::Int => visitor.visit_i32(self.de.reader.read_int()?),
-            TagType::Long => visitor.visit_i64(self.de.reader.read_long()?),
-            TagType::Float => visitor.visit_f32(self.de.reader.read_float()?),
-            TagType::Double => visitor.visit_f64(self.de.reader.read_double()?),
-            TagType::ByteArray | TagType::IntArray | TagType::LongArray | TagType::List => {
-                self.deserialize_seq(visitor)
+            TagType::Byte => visitor.visit_i8(F::read_byte(&mut self.de.reader)?),
+            TagType::Short => visitor.visit_i16(F::read_short(&mut self.de.reader)?),
+            TagType::Int => visitor.visit_i32(F::read_int(&mut self.de.reader)?),
+            TagType::Long => visitor.visit_i64(F::read_long(&mut self.de.reader)?),
+            TagType::Float => visitor.visit_f32(F::read_float(&mut self.de.reader)?),
+            TagType::Double => visitor.visit_f64(F::read_double(&mut self.de.reader)?),
+            // A dynamic Tag can't tell a LongArray from a List of longs once
+            // it's just "a sequence", so array tags are surfaced as a
+            // single-entry map keyed by a sentinel token instead of a seq.
+            // See crate::nbt's *_ARRAY_TOKEN docs.
+            TagType::ByteArray => visitor.visit_map(ArrayMapAccess::new(
+                self.de,
+                crate::nbt::BYTE_ARRAY_TOKEN,
+                1,
+            )),
+            TagType::IntArray => {
+                visitor.visit_map(ArrayMapAccess::new(self.de, crate::nbt::INT_ARRAY_TOKEN, 4))
             }
-            TagType::String => match self.de.reader.read_string(&mut self.de.scratch)? {
+            TagType::LongArray => visitor.visit_map(ArrayMapAccess::new(
+                self.de,
+                crate::nbt::LONG_ARRAY_TOKEN,
+                8,
+            )),
+            TagType::List => self.deserialize_seq(visitor),
+            TagType::String => match self.de.read_string()? {
                 Reference::Copied(s) => visitor.visit_str(s),
                 Reference::Borrowed(s) => visitor.visit_borrowed_str(s),
             },
@@ -128,7 +470,7 @@ where
     where
         V: Visitor<'de>,
     {
-        let byte = self.de.reader.read_byte()?;
+        let byte = F::read_byte(&mut self.de.reader)?;
         match byte {
             0 => visitor.visit_bool(false),
             1 => visitor.visit_bool(true),
@@ -154,32 +496,62 @@ where
             TagType::List => self.de.reader.read_tag_type()?,
             _ => Err(Error::InvalidTagForSeq(self.tag_type))?,
         };
-        let len = self.de.reader.read_int()?;
-        visitor.visit_seq(SeqAccess::new(
-            self.de,
-            element_type,
-            usize::try_from(len).map_err(|_| Error::NegativeLength)?,
-        ))
+        let len = usize::try_from(F::read_int(&mut self.de.reader)?)
+            .map_err(|_| Error::NegativeLength)?;
+        self.de.check_alloc_len(len)?;
+
+        self.de.enter_nesting()?;
+        let result = visitor.visit_seq(SeqAccess::new(&mut *self.de, element_type, len));
+        self.de.exit_nesting();
+        result
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_map(MapAccess::new(self.de))
+        self.de.enter_nesting()?;
+        let result = visitor.visit_map(MapAccess::new(&mut *self.de));
+        self.de.exit_nesting();
+        result
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let len_multiplier = match self.tag_type {
+            TagType::ByteArray => 1,
+            TagType::IntArray => 4,
+            TagType::LongArray => 8,
+            _ => return Err(Error::InvalidTagForBytes(self.tag_type)),
+        };
+
+        match self.de.read_array_bytes(len_multiplier)? {
+            Reference::Copied(s) => visitor.visit_bytes(s),
+            Reference::Borrowed(s) => visitor.visit_borrowed_bytes(s),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
     }
 }
 
-struct MapAccess<'a, R> {
-    de: &'a mut Deserializer<R>,
+struct MapAccess<'a, R, F> {
+    de: &'a mut Deserializer<R, F>,
     value_type: TagType,
 }
 
-impl<'de, 'a, R> MapAccess<'a, R>
+impl<'de, 'a, R, F> MapAccess<'a, R, F>
 where
     R: read::Read<'de>,
+    F: Format,
 {
-    fn new(de: &'a mut Deserializer<R>) -> Self {
+    fn new(de: &'a mut Deserializer<R, F>) -> Self {
         MapAccess {
             de,
             value_type: TagType::End,
@@ -187,9 +559,10 @@ where
     }
 }
 
-impl<'de, 'a, R> de::MapAccess<'de> for MapAccess<'a, R>
+impl<'de, 'a, R, F> de::MapAccess<'de> for MapAccess<'a, R, F>
 where
     R: read::Read<'de>,
+    F: Format,
 {
     type Error = Error;
 
@@ -214,17 +587,99 @@ where
     }
 }
 
-struct SeqAccess<'a, R> {
-    de: &'a mut Deserializer<R>,
+/// Presents an NBT array tag (`ByteArray`/`IntArray`/`LongArray`) as a
+/// single-entry map whose key is the corresponding sentinel token, mirroring
+/// the encoding `Serializer::serialize_newtype_struct` writes. This is what
+/// lets a dynamic `Tag` distinguish an array tag from a `List` during
+/// `deserialize_any`.
+struct ArrayMapAccess<'a, R, F> {
+    de: &'a mut Deserializer<R, F>,
+    token: &'static str,
+    len_multiplier: usize,
+    done: bool,
+}
+
+impl<'a, R, F> ArrayMapAccess<'a, R, F> {
+    fn new(de: &'a mut Deserializer<R, F>, token: &'static str, len_multiplier: usize) -> Self {
+        ArrayMapAccess {
+            de,
+            token,
+            len_multiplier,
+            done: false,
+        }
+    }
+}
+
+impl<'de, 'a, R, F> de::MapAccess<'de> for ArrayMapAccess<'a, R, F>
+where
+    R: read::Read<'de>,
+    F: Format,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.done {
+            return Ok(None);
+        }
+        self.done = true;
+
+        seed.deserialize(serde::de::value::StrDeserializer::<'_, Error>::new(
+            self.token,
+        ))
+        .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(ArrayPayloadDeserializer {
+            de: self.de,
+            len_multiplier: self.len_multiplier,
+        })
+    }
+}
+
+struct ArrayPayloadDeserializer<'a, R, F> {
+    de: &'a mut Deserializer<R, F>,
+    len_multiplier: usize,
+}
+
+impl<'de, 'a, R, F> de::Deserializer<'de> for ArrayPayloadDeserializer<'a, R, F>
+where
+    R: read::Read<'de>,
+    F: Format,
+{
+    type Error = Error;
+
+    forward_to_deserialize_any! { bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier ignored_any }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.de.read_array_bytes(self.len_multiplier)? {
+            Reference::Copied(s) => visitor.visit_bytes(s),
+            Reference::Borrowed(s) => visitor.visit_borrowed_bytes(s),
+        }
+    }
+}
+
+struct SeqAccess<'a, R, F> {
+    de: &'a mut Deserializer<R, F>,
     element_type: TagType,
     remaining: usize,
 }
 
-impl<'de, 'a, R> SeqAccess<'a, R>
+impl<'de, 'a, R, F> SeqAccess<'a, R, F>
 where
     R: read::Read<'de>,
+    F: Format,
 {
-    fn new(de: &'a mut Deserializer<R>, element_type: TagType, remaining: usize) -> Self {
+    fn new(de: &'a mut Deserializer<R, F>, element_type: TagType, remaining: usize) -> Self {
         SeqAccess {
             de,
             element_type,
@@ -233,9 +688,10 @@ where
     }
 }
 
-impl<'de, 'a, R> de::SeqAccess<'de> for SeqAccess<'a, R>
+impl<'de, 'a, R, F> de::SeqAccess<'de> for SeqAccess<'a, R, F>
 where
     R: read::Read<'de>,
+    F: Format,
 {
     type Error = Error;
 
@@ -260,18 +716,18 @@ where
 mod tests {
     use std::io::Cursor;
 
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
 
     use super::*;
 
-    #[derive(Deserialize, PartialEq, Eq, Debug)]
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
     struct ExampleNBT {
         pub name: String,
         pub age: u16,
         pub inventory: Vec<Item>,
     }
 
-    #[derive(Deserialize, PartialEq, Eq, Debug)]
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
     struct Item {
         pub name: String,
         pub count: i32,
@@ -340,4 +796,159 @@ mod tests {
         let example_nbt: ExampleNBT = from_reader(generate_example_reader()).unwrap();
         assert_eq!(example_nbt, generate_example_output());
     }
+
+    // Builds a root compound containing `depth` further nested, singly-keyed
+    // compounds, so the total nesting depth is `depth + 1`.
+    fn generate_nested_compound_vec(depth: usize) -> Vec<u8> {
+        let mut bytes = vec![0x0a, 0x00, 0x00]; // compound, empty name
+        for _ in 0..depth {
+            bytes.extend([0x0a, 0x00, 0x01, b'a']); // field: compound named "a"
+        }
+        for _ in 0..=depth {
+            bytes.push(0x00); // end tag, once per opened compound
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_from_slice_within_default_depth_limit_succeeds() {
+        let bytes = generate_nested_compound_vec(DEFAULT_MAX_DEPTH - 1);
+        assert!(from_slice::<crate::nbt::owned::Tag>(&bytes).is_ok());
+    }
+
+    #[test]
+    fn test_from_slice_beyond_default_depth_limit_errors() {
+        let bytes = generate_nested_compound_vec(DEFAULT_MAX_DEPTH + 1);
+        assert!(matches!(
+            from_slice::<crate::nbt::owned::Tag>(&bytes),
+            Err(Error::RecursionLimitExceeded)
+        ));
+    }
+
+    #[test]
+    fn test_from_slice_with_config_respects_custom_depth_limit() {
+        let bytes = generate_nested_compound_vec(5);
+        assert!(matches!(
+            from_slice_with_config::<crate::nbt::owned::Tag>(&bytes, Config::new().max_depth(3)),
+            Err(Error::RecursionLimitExceeded)
+        ));
+    }
+
+    #[test]
+    fn test_from_slice_errors_on_trailing_data() {
+        let mut bytes = generate_example_vec();
+        bytes.push(0xff);
+
+        assert!(matches!(
+            from_slice::<ExampleNBT>(&bytes),
+            Err(Error::TrailingData)
+        ));
+    }
+
+    #[test]
+    fn test_from_reader_errors_on_trailing_data() {
+        let mut bytes = generate_example_vec();
+        bytes.push(0xff);
+
+        assert!(matches!(
+            from_reader::<_, ExampleNBT>(Cursor::new(bytes)),
+            Err(Error::TrailingData)
+        ));
+    }
+
+    #[test]
+    fn test_from_slice_with_trailing_returns_remaining_bytes() {
+        let mut bytes = generate_example_vec();
+        bytes.extend([0xde, 0xad, 0xbe, 0xef]);
+
+        let (example_nbt, rest): (ExampleNBT, &[u8]) = from_slice_with_trailing(&bytes).unwrap();
+        assert_eq!(example_nbt, generate_example_output());
+        assert_eq!(rest, &[0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    // A compound with a single ByteArray field named "data", holding
+    // `payload`.
+    fn generate_byte_array_vec(payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![
+            0x0a, 0x00, 0x00, // compound, empty name
+            0x07, // byte array
+            0x00, 0x04, b'd', b'a', b't', b'a', // name "data"
+        ];
+        bytes.extend((payload.len() as i32).to_be_bytes());
+        bytes.extend_from_slice(payload);
+        bytes.push(0x00); // end tag
+        bytes
+    }
+
+    #[test]
+    fn test_borrowed_tag_byte_array_is_zero_copy_from_slice() {
+        use crate::nbt::borrowed;
+
+        let bytes = generate_byte_array_vec(&[1, 2, 3, 4]);
+        let payload_ptr = bytes[bytes.len() - 5..bytes.len() - 1].as_ptr();
+
+        let tag: borrowed::Tag = from_slice(&bytes).unwrap();
+        let borrowed::Tag::Compound(fields) = tag else {
+            panic!("expected a compound");
+        };
+        let borrowed::Tag::ByteArray(array) = &fields["data"] else {
+            panic!("expected a byte array");
+        };
+
+        assert_eq!(array.as_raw_slice().as_ptr(), payload_ptr);
+        assert_eq!(array.as_raw_slice(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_from_slice_le_round_trips_with_to_le_writer() {
+        let example_nbt = generate_example_output();
+        let mut bytes = Vec::new();
+        crate::ser::to_le_writer(&mut bytes, &example_nbt).unwrap();
+
+        let decoded: ExampleNBT = from_slice_le(&bytes).unwrap();
+        assert_eq!(decoded, example_nbt);
+    }
+
+    #[test]
+    fn test_from_reader_le_round_trips_with_to_le_writer() {
+        let example_nbt = generate_example_output();
+        let mut bytes = Vec::new();
+        crate::ser::to_le_writer(&mut bytes, &example_nbt).unwrap();
+
+        let decoded: ExampleNBT = from_reader_le(Cursor::new(bytes)).unwrap();
+        assert_eq!(decoded, example_nbt);
+    }
+
+    #[test]
+    fn test_from_slice_varint_round_trips_with_to_network_writer() {
+        let example_nbt = generate_example_output();
+        let mut bytes = Vec::new();
+        crate::ser::to_network_writer(&mut bytes, &example_nbt).unwrap();
+
+        let decoded: ExampleNBT = from_slice_varint(&bytes).unwrap();
+        assert_eq!(decoded, example_nbt);
+    }
+
+    #[test]
+    fn test_from_slice_bedrock_level_dat_skips_header() {
+        let example_nbt = generate_example_output();
+        let mut payload = Vec::new();
+        crate::ser::to_le_writer(&mut payload, &example_nbt).unwrap();
+
+        let mut bytes = Vec::new();
+        bytes.extend(9i32.to_le_bytes()); // format/version word
+        bytes.extend((payload.len() as u32).to_le_bytes());
+        bytes.extend(payload);
+
+        let decoded: ExampleNBT = from_slice_bedrock_level_dat(&bytes).unwrap();
+        assert_eq!(decoded, example_nbt);
+    }
+
+    #[test]
+    fn test_from_slice_bedrock_level_dat_errors_on_truncated_header() {
+        assert!(matches!(
+            from_slice_bedrock_level_dat::<ExampleNBT>(&[0; 4]),
+            Err(Error::Io(_))
+        ));
+    }
 }
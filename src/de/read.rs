@@ -12,29 +12,35 @@ where
     Copied(&'c T),
 }
 
+/// A byte source for deserialization, analogous to `ser::write::Write` on
+/// the serialize side. This trait only reads raw, unencoded bytes; decoding
+/// them into NBT's various endiannesses and varint encodings is
+/// [`super::format::Format`]'s job, not this trait's.
 pub(super) trait Read<'de> {
-    fn read_byte(&mut self) -> Result<i8, Error>;
-    fn read_short(&mut self) -> Result<i16, Error>;
-    fn read_int(&mut self) -> Result<i32, Error>;
-    fn read_long(&mut self) -> Result<i64, Error>;
-    fn read_float(&mut self) -> Result<f32, Error>;
-    fn read_double(&mut self) -> Result<f64, Error>;
+    fn read_raw<const N: usize>(&mut self) -> Result<[u8; N], Error>;
 
     fn read_bytes<'s>(
         &'s mut self,
-        len_multiplier: usize,
+        len: usize,
         scratch: &'s mut Vec<u8>,
     ) -> Result<Reference<'de, 's, [u8]>, Error>;
 
-    fn read_string<'s>(
+    fn read_string_with_len<'s>(
         &'s mut self,
+        len: usize,
         scratch: &'s mut Vec<u8>,
     ) -> Result<Reference<'de, 's, str>, Error>;
-    fn ignore_string(&mut self) -> Result<(), Error>;
+
+    fn skip_raw(&mut self, len: usize) -> Result<(), Error>;
+
+    /// Returns whether no more bytes remain to be read, for trailing-data
+    /// detection after the root value. May consume a byte of lookahead to
+    /// find out, so it's only meaningful once nothing else will be read.
+    fn is_at_eof(&mut self) -> Result<bool, Error>;
 
     fn read_tag_type(&mut self) -> Result<TagType, Error> {
         // TODO(tslnc04): figure out a better way to convert into TagType
-        (self.read_byte()? as u8)
+        (self.read_raw::<1>()?[0])
             .try_into()
             .map_err(|err: TagTypeConversionError<u8>| Error::InvalidTagType(err.0))
     }
@@ -57,63 +63,28 @@ impl<'a, R> Read<'a> for Reader<R>
 where
     R: io::Read,
 {
-    fn read_byte(&mut self) -> Result<i8, Error> {
-        let mut buf = [0];
-        self.reader.read_exact(&mut buf)?;
-        Ok(i8::from_be_bytes(buf))
-    }
-
-    fn read_short(&mut self) -> Result<i16, Error> {
-        let mut buf = [0; 2];
-        self.reader.read_exact(&mut buf)?;
-        Ok(i16::from_be_bytes(buf))
-    }
-
-    fn read_int(&mut self) -> Result<i32, Error> {
-        let mut buf = [0; 4];
-        self.reader.read_exact(&mut buf)?;
-        Ok(i32::from_be_bytes(buf))
-    }
-
-    fn read_long(&mut self) -> Result<i64, Error> {
-        let mut buf = [0; 8];
+    fn read_raw<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut buf = [0; N];
         self.reader.read_exact(&mut buf)?;
-        Ok(i64::from_be_bytes(buf))
-    }
-
-    fn read_float(&mut self) -> Result<f32, Error> {
-        let mut buf = [0; 4];
-        self.reader.read_exact(&mut buf)?;
-        Ok(f32::from_be_bytes(buf))
-    }
-
-    fn read_double(&mut self) -> Result<f64, Error> {
-        let mut buf = [0; 8];
-        self.reader.read_exact(&mut buf)?;
-        Ok(f64::from_be_bytes(buf))
+        Ok(buf)
     }
 
     fn read_bytes<'s>(
         &'s mut self,
-        len_multiplier: usize,
+        len: usize,
         scratch: &'s mut Vec<u8>,
     ) -> Result<Reference<'a, 's, [u8]>, Error> {
-        let mut buf = [0; 4];
-        self.reader.read_exact(&mut buf)?;
-        let len = usize::try_from(i32::from_be_bytes(buf)).map_err(|_| Error::NegativeLength)?;
-        scratch.resize(len * len_multiplier, 0);
+        scratch.resize(len, 0);
         self.reader.read_exact(scratch.as_mut_slice())?;
         Ok(Reference::Copied(scratch.as_slice()))
     }
 
-    fn read_string<'s>(
+    fn read_string_with_len<'s>(
         &'s mut self,
+        len: usize,
         scratch: &'s mut Vec<u8>,
     ) -> Result<Reference<'a, 's, str>, Error> {
-        let mut buf = [0; 2];
-        self.reader.read_exact(&mut buf)?;
-        let len = u16::from_be_bytes(buf);
-        scratch.resize(len as usize, 0);
+        scratch.resize(len, 0);
         self.reader.read_exact(scratch.as_mut_slice())?;
         let converted =
             cesu8::from_java_cesu8(scratch.as_slice()).map_err(|_| Error::InvalidMUTF8)?;
@@ -130,12 +101,20 @@ where
         }
     }
 
-    fn ignore_string(&mut self) -> Result<(), Error> {
-        let len = self.read_short()?;
-        let mut buf = vec![0; len as usize];
+    fn skip_raw(&mut self, len: usize) -> Result<(), Error> {
+        let mut buf = vec![0; len];
         self.reader.read_exact(&mut buf)?;
         Ok(())
     }
+
+    fn is_at_eof(&mut self) -> Result<bool, Error> {
+        let mut buf = [0];
+        match self.reader.read(&mut buf) {
+            Ok(0) => Ok(true),
+            Ok(_) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
 
 pub(super) struct Slice<'a> {
@@ -146,73 +125,46 @@ impl<'a> Slice<'a> {
     pub(super) fn new(slice: &'a [u8]) -> Self {
         Slice { slice }
     }
-}
-
-impl<'a> Read<'a> for Slice<'a> {
-    fn read_byte(&mut self) -> Result<i8, Error> {
-        let (byte, rest) = self.slice.split_at(1);
-        self.slice = rest;
-        Ok(i8::from_be_bytes([byte[0]]))
-    }
 
-    fn read_short(&mut self) -> Result<i16, Error> {
-        let (short, rest) = self.slice.split_at(2);
-        self.slice = rest;
-        Ok(i16::from_be_bytes([short[0], short[1]]))
-    }
-
-    fn read_int(&mut self) -> Result<i32, Error> {
-        let (int, rest) = self.slice.split_at(4);
-        self.slice = rest;
-        Ok(i32::from_be_bytes([int[0], int[1], int[2], int[3]]))
+    pub(super) fn remaining(&self) -> &'a [u8] {
+        self.slice
     }
 
-    fn read_long(&mut self) -> Result<i64, Error> {
-        let (long, rest) = self.slice.split_at(8);
-        self.slice = rest;
-        Ok(i64::from_be_bytes([
-            long[0], long[1], long[2], long[3], long[4], long[5], long[6], long[7],
-        ]))
-    }
+    /// Splits off the first `len` bytes, the same as `self.slice.split_at`,
+    /// but returns `Error::Io`/`UnexpectedEof` instead of panicking when
+    /// `len` exceeds the remaining input: a declared length from untrusted
+    /// input shouldn't be able to crash the process just because it's
+    /// larger than what's actually left.
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.slice.len() {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
 
-    fn read_float(&mut self) -> Result<f32, Error> {
-        let (float, rest) = self.slice.split_at(4);
+        let (bytes, rest) = self.slice.split_at(len);
         self.slice = rest;
-        Ok(f32::from_be_bytes([float[0], float[1], float[2], float[3]]))
+        Ok(bytes)
     }
+}
 
-    fn read_double(&mut self) -> Result<f64, Error> {
-        let (double, rest) = self.slice.split_at(8);
-        self.slice = rest;
-        Ok(f64::from_be_bytes([
-            double[0], double[1], double[2], double[3], double[4], double[5], double[6], double[7],
-        ]))
+impl<'a> Read<'a> for Slice<'a> {
+    fn read_raw<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().unwrap())
     }
 
     fn read_bytes<'s>(
         &'s mut self,
-        len_multiplier: usize,
+        len: usize,
         _scratch: &'s mut Vec<u8>,
     ) -> Result<Reference<'a, 's, [u8]>, Error> {
-        let (len_bytes, rest) = self.slice.split_at(4);
-        self.slice = rest;
-        let len = i32::from_be_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]);
-        let (bytes, rest) = self
-            .slice
-            .split_at(usize::try_from(len).map_err(|_| Error::NegativeLength)? * len_multiplier);
-        self.slice = rest;
-        Ok(Reference::Borrowed(bytes))
+        Ok(Reference::Borrowed(self.take(len)?))
     }
 
-    fn read_string<'s>(
+    fn read_string_with_len<'s>(
         &'s mut self,
+        len: usize,
         scratch: &'s mut Vec<u8>,
     ) -> Result<Reference<'a, 's, str>, Error> {
-        let (len_bytes, rest) = self.slice.split_at(2);
-        self.slice = rest;
-        let len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]);
-        let (string, rest) = self.slice.split_at(len as usize);
-        self.slice = rest;
+        let string = self.take(len)?;
         let converted = cesu8::from_java_cesu8(string).map_err(|_| Error::InvalidMUTF8)?;
         match converted {
             Cow::Borrowed(s) => Ok(Reference::Borrowed(s)),
@@ -225,10 +177,12 @@ impl<'a> Read<'a> for Slice<'a> {
         }
     }
 
-    fn ignore_string(&mut self) -> Result<(), Error> {
-        let len = self.read_short()?;
-        let (_string, rest) = self.slice.split_at(len as usize);
-        self.slice = rest;
+    fn skip_raw(&mut self, len: usize) -> Result<(), Error> {
+        self.take(len)?;
         Ok(())
     }
+
+    fn is_at_eof(&mut self) -> Result<bool, Error> {
+        Ok(self.slice.is_empty())
+    }
 }
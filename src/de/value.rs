@@ -0,0 +1,170 @@
+use serde::{de, de::IntoDeserializer, forward_to_deserialize_any, Deserialize};
+
+use crate::nbt::{owned::Tag, Compound};
+
+use super::Error;
+
+/// Deserializes `T` from an already-parsed [`Tag`] tree, decoupling parsing
+/// (bytes -> `Tag`) from typed extraction (`Tag` -> `T`), for callers who
+/// already have a tree in hand (e.g. from [`crate::nbt::owned`] construction
+/// or a prior parse) and want to extract a typed struct from it without
+/// re-encoding and re-parsing.
+pub fn from_tag<'de, T>(tag: &'de Tag) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    T::deserialize(tag)
+}
+
+impl<'de> de::Deserializer<'de> for &'de Tag {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+        byte_buf unit unit_struct newtype_struct seq tuple tuple_struct map
+        struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Tag::End => visitor.visit_unit(),
+            Tag::Byte(v) => visitor.visit_i8(*v),
+            Tag::Short(v) => visitor.visit_i16(*v),
+            Tag::Int(v) => visitor.visit_i32(*v),
+            Tag::Long(v) => visitor.visit_i64(*v),
+            Tag::Float(v) => visitor.visit_f32(*v),
+            Tag::Double(v) => visitor.visit_f64(*v),
+            Tag::ByteArray(v) => visitor.visit_seq(ArraySeqAccess {
+                iter: (0..v.len()).map(move |i| v.get(i).unwrap()),
+            }),
+            Tag::String(v) => visitor.visit_borrowed_str(v),
+            Tag::List(v) => visitor.visit_seq(TagSeqAccess { iter: v.iter() }),
+            Tag::Compound(v) => visitor.visit_map(TagMapAccess::new(v)),
+            Tag::IntArray(v) => visitor.visit_seq(ArraySeqAccess {
+                iter: (0..v.len()).map(move |i| v.get(i).unwrap()),
+            }),
+            Tag::LongArray(v) => visitor.visit_seq(ArraySeqAccess {
+                iter: (0..v.len()).map(move |i| v.get(i).unwrap()),
+            }),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+}
+
+/// Presents a scalar array's elements (`ByteArray`/`IntArray`/`LongArray`) as
+/// a serde sequence, deserializing each element through serde's blanket
+/// `IntoDeserializer` impl for primitives rather than a hand-written scalar
+/// `Deserializer`.
+struct ArraySeqAccess<I> {
+    iter: I,
+}
+
+impl<'de, I, T> de::SeqAccess<'de> for ArraySeqAccess<I>
+where
+    I: Iterator<Item = T>,
+    T: IntoDeserializer<'de, Error>,
+{
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed.deserialize(value.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        }
+    }
+}
+
+/// Presents a `Tag::List`'s elements as a serde sequence, recursing back into
+/// this module's `Deserializer` impl for `&Tag` for each element.
+struct TagSeqAccess<'de> {
+    iter: std::slice::Iter<'de, Tag>,
+}
+
+impl<'de> de::SeqAccess<'de> for TagSeqAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(tag) => seed.deserialize(tag).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Presents a `Tag::Compound`'s entries as a serde map, generic over
+/// whichever concrete map type [`Compound`] currently aliases to.
+struct TagMapAccess<'de> {
+    iter: <&'de Compound<String, Tag> as IntoIterator>::IntoIter,
+    value: Option<&'de Tag>,
+}
+
+impl<'de> TagMapAccess<'de> {
+    fn new(map: &'de Compound<String, Tag>) -> Self {
+        TagMapAccess {
+            iter: map.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for TagMapAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(serde::de::value::StrDeserializer::<'_, Error>::new(key))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(upper),
+            _ => None,
+        }
+    }
+}
@@ -1,29 +1,27 @@
 use counter::Counter;
 
-use crate::{chunk_format::BlockStates, Chunk};
+use crate::Chunk;
 
-/// The distribution of block states in the section. Returns a vector with
-/// the same length of palette, with each element being the number of blocks
-/// with that state.
-fn distribution(block_states: &BlockStates) -> Vec<u64> {
-    if block_states.data.is_none() {
-        return vec![16 * 16 * 16];
-    }
-
-    let mut distribution = vec![0; block_states.palette.len()];
-    for index in block_states.unpack_data() {
-        distribution[index as usize] += 1;
-    }
-    distribution
-}
-
-pub fn chunk(chunk: Chunk) -> Counter<String, u64> {
+/// Aggregates a chunk's block states into a `Counter`, keyed on either the
+/// palette entry's bare `name` or, if `properties` is true, its full state
+/// via [`BlockStatePalette::state_string`].
+pub fn chunk(chunk: Chunk, properties: bool) -> Counter<String, u64> {
+    let data_version = chunk.data_version;
     let mut chunk_distribution = Counter::new();
     for section in chunk.sections {
-        if let Some(block_states) = section.block_states {
-            for (count, palette) in distribution(&block_states).iter().zip(block_states.palette) {
+        if let Some(mut block_states) = section.block_states {
+            for (count, palette) in block_states
+                .distribution(data_version)
+                .iter()
+                .zip(block_states.palette)
+            {
+                let key = if properties {
+                    palette.state_string()
+                } else {
+                    palette.name
+                };
                 chunk_distribution
-                    .entry(palette.name)
+                    .entry(key)
                     .and_modify(|e| *e += count)
                     .or_insert(*count);
             }
@@ -22,5 +22,5 @@ pub mod ser;
 pub mod unpack;
 
 pub use anvil::parse_chunk_at;
-pub use anvil::parse_region;
+pub use anvil::RegionReader;
 pub use chunk_format::Chunk;
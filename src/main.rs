@@ -1,8 +1,13 @@
-use std::{fs, io::Cursor, ops::Add as _, path::PathBuf};
+use std::{
+    fs,
+    io::Cursor,
+    ops::Add as _,
+    path::{Path, PathBuf},
+};
 
 use clap::Parser;
 use counter::Counter;
-use nombrilo::{anvil::parse_region, distribution, Chunk};
+use nombrilo::{distribution, Chunk, RegionReader};
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use tabled::{settings::Style, Table, Tabled};
 
@@ -25,6 +30,12 @@ struct Cli {
     #[arg(short, long)]
     sorted: bool,
 
+    /// Aggregate by full block state (name plus properties, e.g.
+    /// `minecraft:oak_log[axis=x]`) instead of just the block name. Default
+    /// is false.
+    #[arg(short, long)]
+    properties: bool,
+
     /// Print additional information, including time taken. Default is false.
     #[arg(short, long)]
     verbose: bool,
@@ -62,10 +73,14 @@ fn flatten_path(region: PathBuf) -> Vec<PathBuf> {
         .collect()
 }
 
-fn parse_file(region: PathBuf) -> Vec<Chunk> {
+fn parse_file(region: PathBuf) -> impl Iterator<Item = Chunk> {
+    let region_dir = region.parent().unwrap_or(Path::new(".")).to_path_buf();
     let file = fs::read(region).unwrap();
-    let mut reader = Cursor::new(file);
-    parse_region(&mut reader).unwrap()
+    let reader = Cursor::new(file);
+    RegionReader::new(reader, region_dir)
+        .unwrap()
+        .into_iter()
+        .map(Result::unwrap)
 }
 
 fn main() {
@@ -73,6 +88,7 @@ fn main() {
 
     let cli = Cli::parse();
     let regions = cli.region.unwrap_or(vec![".".into()]);
+    let properties = cli.properties;
 
     let mut block_distribution = regions
         .into_iter()
@@ -80,7 +96,7 @@ fn main() {
         .par_bridge()
         .map(parse_file)
         .flatten()
-        .map(distribution::chunk)
+        .map(|chunk| distribution::chunk(chunk, properties))
         .reduce(Counter::<String, u64>::new, Counter::add);
 
     if let Some(ignore) = cli.ignore {
@@ -2,7 +2,10 @@ use std::{borrow::Cow, slice};
 
 use serde::{de::Visitor, Deserialize, Serialize};
 
-use crate::unpack;
+use crate::{
+    nbt::{owned, Compound},
+    unpack,
+};
 
 macro_rules! impl_array_deserialize {
     ($($array:ident)*) => {
@@ -118,6 +121,12 @@ impl<'a> ByteArray<'a> {
     pub fn len(&self) -> usize {
         self.inner.len()
     }
+
+    /// Detaches this array from its borrowed lifetime, copying the payload
+    /// only if it isn't already [`Cow::Owned`].
+    pub fn into_owned(self) -> owned::ByteArray {
+        owned::ByteArray::new(self.inner.into_owned())
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -127,16 +136,27 @@ pub struct IntArray<'a> {
 }
 
 impl<'a> IntArray<'a> {
-    pub fn as_slice(&mut self) -> &'a [i32] {
+    /// Reinterprets the (already native-endian) payload as `&[i32]` without
+    /// copying, if `inner` happens to be aligned for `i32`. `inner` is only
+    /// guaranteed 1-byte aligned, so this fails on targets and allocations
+    /// where that isn't enough; use [`IntArray::to_vec`] when that's not
+    /// acceptable.
+    pub fn try_as_slice(&mut self) -> Result<&[i32], bytemuck::PodCastError> {
         if !self.native_endian {
             self.swap_endianness();
         }
 
-        unsafe { slice::from_raw_parts(self.inner.as_ptr() as *const i32, self.inner.len() / 4) }
+        bytemuck::try_cast_slice(&self.inner)
     }
 
+    /// Like [`IntArray::try_as_slice`], but always succeeds by copying into a
+    /// freshly allocated, correctly aligned `Vec<i32>`.
     pub fn to_vec(&mut self) -> Vec<i32> {
-        self.as_slice().to_vec()
+        if !self.native_endian {
+            self.swap_endianness();
+        }
+
+        bytemuck::pod_collect_to_vec(&self.inner)
     }
 
     pub fn len(&self) -> usize {
@@ -148,6 +168,20 @@ impl<'a> IntArray<'a> {
         self.inner = Cow::Owned(swapped);
         self.native_endian = true;
     }
+
+    /// Detaches this array from its borrowed lifetime, normalizing to
+    /// big-endian first (copying the payload if it isn't already
+    /// [`Cow::Owned`]) so that [`owned::IntArray::new`]'s assumption that its
+    /// input is big-endian holds.
+    pub fn into_owned(self) -> owned::IntArray {
+        let bytes = if self.big_endian() {
+            self.inner.into_owned()
+        } else {
+            unpack::swap_endianness_32bit(self.inner.as_ref())
+        };
+
+        owned::IntArray::new(bytes)
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -157,16 +191,27 @@ pub struct LongArray<'a> {
 }
 
 impl<'a> LongArray<'a> {
-    pub fn as_slice(&mut self) -> &'a [i64] {
+    /// Reinterprets the (already native-endian) payload as `&[i64]` without
+    /// copying, if `inner` happens to be aligned for `i64`. `inner` is only
+    /// guaranteed 1-byte aligned, so this fails on targets and allocations
+    /// where that isn't enough; use [`LongArray::to_vec`] when that's not
+    /// acceptable.
+    pub fn try_as_slice(&mut self) -> Result<&[i64], bytemuck::PodCastError> {
         if !self.native_endian {
             self.swap_endianness();
         }
 
-        unsafe { slice::from_raw_parts(self.inner.as_ptr() as *const i64, self.inner.len() / 8) }
+        bytemuck::try_cast_slice(&self.inner)
     }
 
+    /// Like [`LongArray::try_as_slice`], but always succeeds by copying into
+    /// a freshly allocated, correctly aligned `Vec<i64>`.
     pub fn to_vec(&mut self) -> Vec<i64> {
-        self.as_slice().to_vec()
+        if !self.native_endian {
+            self.swap_endianness();
+        }
+
+        bytemuck::pod_collect_to_vec(&self.inner)
     }
 
     pub fn len(&self) -> usize {
@@ -178,6 +223,326 @@ impl<'a> LongArray<'a> {
         self.inner = Cow::Owned(swapped);
         self.native_endian = true;
     }
+
+    /// Detaches this array from its borrowed lifetime, normalizing to
+    /// big-endian first (copying the payload if it isn't already
+    /// [`Cow::Owned`]) so that [`owned::LongArray::new`]'s assumption that
+    /// its input is big-endian holds.
+    pub fn into_owned(self) -> owned::LongArray {
+        let bytes = if self.big_endian() {
+            self.inner.into_owned()
+        } else {
+            unpack::swap_endianness_64bit(self.inner.as_ref())
+        };
+
+        owned::LongArray::new(bytes)
+    }
 }
 
 impl_array_deserialize! { ByteArray IntArray LongArray }
+
+/// Borrowing counterpart to [`crate::nbt::owned::Tag`], for inspecting
+/// schema-less NBT without copying every string and byte array out of the
+/// input. Strings borrow via [`Cow::Borrowed`] whenever the underlying
+/// reader can hand out a `&'de str` directly (the slice reader behind
+/// [`crate::de::from_slice`]), and fall back to [`Cow::Owned`] otherwise
+/// (e.g. the `io::Read` reader behind [`crate::de::from_reader`], which
+/// can't borrow, or MUTF-8 that needed re-encoding).
+#[derive(Debug, Clone)]
+pub enum Tag<'a> {
+    End,
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(ByteArray<'a>),
+    String(Cow<'a, str>),
+    List(Vec<Tag<'a>>),
+    Compound(Compound<Cow<'a, str>, Tag<'a>>),
+    IntArray(IntArray<'a>),
+    LongArray(LongArray<'a>),
+}
+
+impl<'a> Tag<'a> {
+    /// Recursively clones this tag's borrowed data and detaches it from
+    /// `'a`, producing the `'static` [`owned::Tag`]. Prefer
+    /// [`Tag::into_owned`] when the original `'a` borrow doesn't need to
+    /// outlive the conversion, since it reuses already-owned `Cow::Owned`
+    /// data instead of cloning it again.
+    pub fn to_owned(&self) -> owned::Tag {
+        self.clone().into_owned()
+    }
+
+    /// Detaches this tag from its borrowed lifetime, copying every
+    /// [`Cow::Borrowed`] string/array payload and leaving already-owned data
+    /// untouched.
+    pub fn into_owned(self) -> owned::Tag {
+        match self {
+            Tag::End => owned::Tag::End,
+            Tag::Byte(v) => owned::Tag::Byte(v),
+            Tag::Short(v) => owned::Tag::Short(v),
+            Tag::Int(v) => owned::Tag::Int(v),
+            Tag::Long(v) => owned::Tag::Long(v),
+            Tag::Float(v) => owned::Tag::Float(v),
+            Tag::Double(v) => owned::Tag::Double(v),
+            Tag::ByteArray(v) => owned::Tag::ByteArray(v.into_owned()),
+            Tag::String(v) => owned::Tag::String(v.into_owned()),
+            Tag::List(v) => owned::Tag::List(v.into_iter().map(Tag::into_owned).collect()),
+            Tag::Compound(v) => owned::Tag::Compound(
+                v.into_iter()
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect(),
+            ),
+            Tag::IntArray(v) => owned::Tag::IntArray(v.into_owned()),
+            Tag::LongArray(v) => owned::Tag::LongArray(v.into_owned()),
+        }
+    }
+}
+
+impl<'a> Serialize for Tag<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Tag::End => serializer.serialize_unit(),
+            Tag::Byte(v) => serializer.serialize_i8(*v),
+            Tag::Short(v) => serializer.serialize_i16(*v),
+            Tag::Int(v) => serializer.serialize_i32(*v),
+            Tag::Long(v) => serializer.serialize_i64(*v),
+            Tag::Float(v) => serializer.serialize_f32(*v),
+            Tag::Double(v) => serializer.serialize_f64(*v),
+            Tag::ByteArray(v) => v.serialize(serializer),
+            Tag::String(v) => serializer.serialize_str(v),
+            Tag::List(v) => v.serialize(serializer),
+            Tag::Compound(v) => v.serialize(serializer),
+            Tag::IntArray(v) => v.serialize(serializer),
+            Tag::LongArray(v) => v.serialize(serializer),
+        }
+    }
+}
+
+/// Seed that deserializes a string key/value as a [`Cow<'de, str>`],
+/// borrowing instead of allocating whenever the deserializer can hand out a
+/// `&'de str`. The blanket `Deserialize` impl `serde` provides for `Cow`
+/// always allocates, so `Tag` uses this instead of `map.next_key::<Cow<str>>()`.
+struct CowStrSeed;
+
+impl<'de> serde::de::DeserializeSeed<'de> for CowStrSeed {
+    type Value = Cow<'de, str>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct CowStrVisitor;
+
+        impl<'de> Visitor<'de> for CowStrVisitor {
+            type Value = Cow<'de, str>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Cow::Owned(v.to_string()))
+            }
+
+            fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+                Ok(Cow::Owned(v))
+            }
+
+            fn visit_borrowed_str<E: serde::de::Error>(
+                self,
+                v: &'de str,
+            ) -> Result<Self::Value, E> {
+                Ok(Cow::Borrowed(v))
+            }
+        }
+
+        deserializer.deserialize_str(CowStrVisitor)
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for Tag<'a>
+where
+    'de: 'a,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TagVisitor;
+
+        impl<'de> Visitor<'de> for TagVisitor {
+            type Value = Tag<'de>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a valid NBT tag")
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(Tag::Byte(v as i8))
+            }
+
+            fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::Byte(v))
+            }
+
+            fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::Short(v))
+            }
+
+            fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::Int(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::Long(v))
+            }
+
+            fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::Float(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::Double(v))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::String(Cow::Owned(v.to_string())))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::String(Cow::Owned(v)))
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Tag::String(Cow::Borrowed(v)))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut tags = Vec::new();
+                if let Some(len) = seq.size_hint() {
+                    tags.reserve_exact(len.min(crate::nbt::MAX_PREALLOCATED_ELEMENTS));
+                }
+
+                while let Some(tag) = seq.next_element::<Tag<'de>>()? {
+                    tags.push(tag);
+                }
+
+                // pretend that every sequence is a list
+                Ok(Tag::List(tags))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                // A ByteArray/IntArray/LongArray tag is surfaced by the
+                // deserializer as a single-entry map keyed on one of the
+                // array sentinel tokens, so that it can be told apart from a
+                // List here. Any other key means this is a real Compound.
+                let key = match map.next_key_seed(CowStrSeed)? {
+                    Some(key) => key,
+                    None => return Ok(Tag::Compound(Compound::new())),
+                };
+
+                match key.as_ref() {
+                    crate::nbt::BYTE_ARRAY_TOKEN => Ok(Tag::ByteArray(ByteArray::new(
+                        map.next_value_seed(RawBytesSeed)?,
+                    ))),
+                    crate::nbt::INT_ARRAY_TOKEN => Ok(Tag::IntArray(IntArray::new(
+                        map.next_value_seed(RawBytesSeed)?,
+                    ))),
+                    crate::nbt::LONG_ARRAY_TOKEN => Ok(Tag::LongArray(LongArray::new(
+                        map.next_value_seed(RawBytesSeed)?,
+                    ))),
+                    _ => {
+                        let mut tags = Compound::new();
+                        tags.insert(key, map.next_value::<Tag<'de>>()?);
+
+                        while let Some(key) = map.next_key_seed(CowStrSeed)? {
+                            tags.insert(key, map.next_value::<Tag<'de>>()?);
+                        }
+
+                        Ok(Tag::Compound(tags))
+                    }
+                }
+            }
+        }
+
+        deserializer.deserialize_any(TagVisitor)
+    }
+}
+
+/// Seed that pulls the raw bytes out of an array sentinel's value as a
+/// [`Cow<'de, [u8]>`], borrowing instead of allocating whenever the
+/// deserializer can hand out a `&'de [u8]`. Used to reconstruct
+/// `Tag::ByteArray`/`IntArray`/`LongArray` in `visit_map` above.
+struct RawBytesSeed;
+
+impl<'de> serde::de::DeserializeSeed<'de> for RawBytesSeed {
+    type Value = Cow<'de, [u8]>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct RawBytesVisitor;
+
+        impl<'de> Visitor<'de> for RawBytesVisitor {
+            type Value = Cow<'de, [u8]>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("raw NBT array bytes")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(Cow::Owned(v.to_vec()))
+            }
+
+            fn visit_borrowed_bytes<E: serde::de::Error>(
+                self,
+                v: &'de [u8],
+            ) -> Result<Self::Value, E> {
+                Ok(Cow::Borrowed(v))
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(Cow::Owned(v))
+            }
+        }
+
+        deserializer.deserialize_bytes(RawBytesVisitor)
+    }
+}
@@ -1,6 +1,34 @@
 pub mod borrowed;
 pub mod owned;
 
+/// The map type backing `Tag::Compound`. Plain `HashMap` by default;
+/// enabling the `preserve_order` feature (mirroring valence_nbt) swaps it
+/// for `indexmap::IndexMap` so that the on-disk key order of a compound
+/// survives a parse/re-serialize round trip, which matters for chunk data
+/// whose bytes are signed or hashed. `Tag`'s `Serialize` impl just forwards
+/// to this map's own `Serialize`, so the two backends are interchangeable
+/// at every call site that builds or walks a `Compound`.
+#[cfg(not(feature = "preserve_order"))]
+pub(crate) type Compound<K, V> = std::collections::HashMap<K, V>;
+#[cfg(feature = "preserve_order")]
+pub(crate) type Compound<K, V> = indexmap::IndexMap<K, V>;
+
+/// Ceiling on how many elements `Tag`'s `visit_seq` will eagerly
+/// `reserve_exact` for, regardless of the untrusted `SeqAccess::size_hint`
+/// a hostile blob can claim: the `Vec` still grows past this via ordinary
+/// `push` as elements keep arriving, so this only bounds the size of the
+/// single upfront allocation.
+pub(crate) const MAX_PREALLOCATED_ELEMENTS: usize = 4096;
+
+/// Sentinel newtype-struct names used to tell the binary (de)serializer that
+/// the wrapped payload is a true NBT `ByteArray`/`IntArray`/`LongArray` tag
+/// rather than a `List`, the same technique fastnbt uses. `deserialize_any`
+/// on a dynamic value otherwise can't distinguish a `LongArray` from a
+/// `List` of longs.
+pub(crate) const BYTE_ARRAY_TOKEN: &str = "__nbt_byte_array__";
+pub(crate) const INT_ARRAY_TOKEN: &str = "__nbt_int_array__";
+pub(crate) const LONG_ARRAY_TOKEN: &str = "__nbt_long_array__";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum TagType {
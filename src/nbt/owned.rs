@@ -1,8 +1,6 @@
-use std::collections::HashMap;
-
 use serde::{de::Visitor, Deserialize, Serialize};
 
-use crate::unpack;
+use crate::{nbt::Compound, unpack};
 
 macro_rules! impl_array_deserialize {
     ($($array:ident)*) => {
@@ -90,7 +88,7 @@ macro_rules! impl_array_deserialize {
     };
 }
 
-#[derive(Default, Debug, Clone, Serialize)]
+#[derive(Default, Debug, Clone)]
 pub struct ByteArray {
     native_endian: bool,
     inner: Vec<u8>,
@@ -110,21 +108,45 @@ impl ByteArray {
     }
 }
 
-#[derive(Default, Debug, Clone, Serialize)]
+impl Serialize for ByteArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Endianness is irrelevant for single-byte elements, so the raw bytes
+        // can be written as-is.
+        serializer.serialize_bytes(self.as_raw_slice())
+    }
+}
+
+#[derive(Default, Debug, Clone)]
 pub struct IntArray {
     native_endian: bool,
     inner: Vec<u8>,
 }
 
 impl IntArray {
-    pub fn as_slice(&mut self) -> &[i32] {
+    /// Reinterprets the (already native-endian) payload as `&[i32]` without
+    /// copying, if `inner` happens to be aligned for `i32`. `inner` is only
+    /// guaranteed 1-byte aligned, so this fails on targets and allocations
+    /// where that isn't enough; use [`IntArray::to_vec`] when that's not
+    /// acceptable.
+    pub fn try_as_slice(&mut self) -> Result<&[i32], bytemuck::PodCastError> {
         if !self.native_endian {
             self.swap_endianness();
         }
 
-        unsafe {
-            std::slice::from_raw_parts(self.inner.as_ptr() as *const i32, self.inner.len() / 4)
+        bytemuck::try_cast_slice(&self.inner)
+    }
+
+    /// Like [`IntArray::try_as_slice`], but always succeeds by copying into a
+    /// freshly allocated, correctly aligned `Vec<i32>`.
+    pub fn to_vec(&mut self) -> Vec<i32> {
+        if !self.native_endian {
+            self.swap_endianness();
         }
+
+        bytemuck::pod_collect_to_vec(&self.inner)
     }
 
     pub fn get(&self, index: usize) -> Option<i32> {
@@ -159,23 +181,56 @@ impl IntArray {
         self.inner = swapped;
         self.native_endian = true;
     }
+
+    /// Returns the raw bytes in big endian order, as required on the wire,
+    /// regardless of the current in-memory representation.
+    fn to_be_bytes(&self) -> Vec<u8> {
+        if self.big_endian() {
+            self.inner.clone()
+        } else {
+            unpack::swap_endianness_32bit(&self.inner)
+        }
+    }
 }
 
-#[derive(Default, Debug, Clone, Serialize)]
+impl Serialize for IntArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer
+            .serialize_newtype_struct(crate::nbt::INT_ARRAY_TOKEN, &RawBytes(&self.to_be_bytes()))
+    }
+}
+
+#[derive(Default, Debug, Clone)]
 pub struct LongArray {
     native_endian: bool,
     inner: Vec<u8>,
 }
 
 impl LongArray {
-    pub fn as_slice(&mut self) -> &[i64] {
+    /// Reinterprets the (already native-endian) payload as `&[i64]` without
+    /// copying, if `inner` happens to be aligned for `i64`. `inner` is only
+    /// guaranteed 1-byte aligned, so this fails on targets and allocations
+    /// where that isn't enough; use [`LongArray::to_vec`] when that's not
+    /// acceptable.
+    pub fn try_as_slice(&mut self) -> Result<&[i64], bytemuck::PodCastError> {
         if !self.native_endian {
             self.swap_endianness();
         }
 
-        unsafe {
-            std::slice::from_raw_parts(self.inner.as_ptr() as *const i64, self.inner.len() / 8)
+        bytemuck::try_cast_slice(&self.inner)
+    }
+
+    /// Like [`LongArray::try_as_slice`], but always succeeds by copying into
+    /// a freshly allocated, correctly aligned `Vec<i64>`.
+    pub fn to_vec(&mut self) -> Vec<i64> {
+        if !self.native_endian {
+            self.swap_endianness();
         }
+
+        bytemuck::pod_collect_to_vec(&self.inner)
     }
 
     pub fn get(&self, index: usize) -> Option<i64> {
@@ -218,10 +273,46 @@ impl LongArray {
         self.inner = swapped;
         self.native_endian = true;
     }
+
+    /// Returns the raw bytes in big endian order, as required on the wire,
+    /// regardless of the current in-memory representation.
+    fn to_be_bytes(&self) -> Vec<u8> {
+        if self.big_endian() {
+            self.inner.clone()
+        } else {
+            unpack::swap_endianness_64bit(&self.inner)
+        }
+    }
+}
+
+impl Serialize for LongArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer
+            .serialize_newtype_struct(crate::nbt::LONG_ARRAY_TOKEN, &RawBytes(&self.to_be_bytes()))
+    }
 }
 
 impl_array_deserialize! { ByteArray IntArray LongArray }
 
+/// Thin `Serialize` wrapper around a raw byte slice, used to carry the
+/// already-byte-order-corrected payload of an `IntArray`/`LongArray` through
+/// `serialize_newtype_struct` to the binary serializer's `serialize_bytes`.
+struct RawBytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for RawBytes<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Owned, `'static` counterpart to [`crate::nbt::borrowed::Tag`], produced by
+/// [`crate::nbt::borrowed::Tag::to_owned`]/[`crate::nbt::borrowed::Tag::into_owned`].
 #[derive(Debug, Clone)]
 pub enum Tag {
     End,
@@ -234,7 +325,7 @@ pub enum Tag {
     ByteArray(ByteArray),
     String(String),
     List(Vec<Tag>),
-    Compound(HashMap<String, Tag>),
+    Compound(Compound<String, Tag>),
     IntArray(IntArray),
     LongArray(LongArray),
 }
@@ -339,7 +430,7 @@ impl<'de> Deserialize<'de> for Tag {
             {
                 let mut tags = Vec::new();
                 if let Some(len) = seq.size_hint() {
-                    tags.reserve_exact(len);
+                    tags.reserve_exact(len.min(crate::nbt::MAX_PREALLOCATED_ELEMENTS));
                 }
 
                 while let Some(tag) = seq.next_element::<Tag>()? {
@@ -354,16 +445,79 @@ impl<'de> Deserialize<'de> for Tag {
             where
                 A: serde::de::MapAccess<'de>,
             {
-                let mut tags = HashMap::new();
+                // A ByteArray/IntArray/LongArray tag is surfaced by the
+                // deserializer as a single-entry map keyed on one of the
+                // array sentinel tokens, so that it can be told apart from a
+                // List here. Any other key means this is a real Compound.
+                let key = match map.next_key::<String>()? {
+                    Some(key) => key,
+                    None => return Ok(Tag::Compound(Compound::new())),
+                };
+
+                match key.as_str() {
+                    crate::nbt::BYTE_ARRAY_TOKEN => Ok(Tag::ByteArray(ByteArray::new(
+                        map.next_value_seed(RawBytesSeed)?,
+                    ))),
+                    crate::nbt::INT_ARRAY_TOKEN => Ok(Tag::IntArray(IntArray::new(
+                        map.next_value_seed(RawBytesSeed)?,
+                    ))),
+                    crate::nbt::LONG_ARRAY_TOKEN => Ok(Tag::LongArray(LongArray::new(
+                        map.next_value_seed(RawBytesSeed)?,
+                    ))),
+                    _ => {
+                        let mut tags = Compound::new();
+                        tags.insert(key, map.next_value::<Tag>()?);
+
+                        while let Some((key, value)) = map.next_entry()? {
+                            tags.insert(key, value);
+                        }
 
-                while let Some((key, value)) = map.next_entry()? {
-                    tags.insert(key, value);
+                        Ok(Tag::Compound(tags))
+                    }
                 }
-
-                Ok(Tag::Compound(tags))
             }
         }
 
         deserializer.deserialize_any(TagVisitor)
     }
 }
+
+/// Seed that pulls the raw bytes out of an array sentinel's value, used to
+/// reconstruct `Tag::ByteArray`/`IntArray`/`LongArray` in `visit_map` above.
+struct RawBytesSeed;
+
+impl<'de> serde::de::DeserializeSeed<'de> for RawBytesSeed {
+    type Value = Vec<u8>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct RawBytesVisitor;
+
+        impl<'de> Visitor<'de> for RawBytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("raw NBT array bytes")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+
+            fn visit_borrowed_bytes<E: serde::de::Error>(
+                self,
+                v: &'de [u8],
+            ) -> Result<Self::Value, E> {
+                Ok(v.to_vec())
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(v)
+            }
+        }
+
+        deserializer.deserialize_bytes(RawBytesVisitor)
+    }
+}
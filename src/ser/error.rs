@@ -11,6 +11,8 @@ pub enum Error {
     SequenceTooBig,
     UnknownLength,
     CompoundKey,
+    BufferFull,
+    HeterogeneousSequence,
 }
 
 impl ser::Error for Error {
@@ -46,6 +48,11 @@ impl Display for Error {
                 f,
                 "CompoundKey: map keys must be non-None and non-unit scalar types"
             ),
+            Error::BufferFull => write!(f, "BufferFull: the output buffer is not large enough"),
+            Error::HeterogeneousSequence => write!(
+                f,
+                "HeterogeneousSequence: NBT lists must have a single element type"
+            ),
         }
     }
 }
@@ -1,230 +1,502 @@
-use std::io::{self, Write};
+use super::{write::Write, Error};
 
 use crate::nbt::TagType;
 
 pub(super) trait Formatter {
-    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> io::Result<()>
+    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> Result<(), Error>
     where
         W: Write;
-    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> io::Result<()>
+    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> Result<(), Error>
     where
         W: Write;
-    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> io::Result<()>
+    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> Result<(), Error>
     where
         W: Write;
-    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> io::Result<()>
+    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> Result<(), Error>
     where
         W: Write;
-    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> io::Result<()>
+    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> Result<(), Error>
     where
         W: Write;
-    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> io::Result<()>
+    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> Result<(), Error>
     where
         W: Write;
-    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> io::Result<()>
+    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> Result<(), Error>
     where
         W: Write;
-    fn write_byte_array<W>(&mut self, writer: &mut W, value: &[u8]) -> io::Result<()>
+    fn write_byte_array<W>(&mut self, writer: &mut W, value: &[u8]) -> Result<(), Error>
     where
         W: Write;
-    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> io::Result<()>
+    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> Result<(), Error>
     where
         W: Write;
 
-    fn start_byte_array<W>(&mut self, writer: &mut W, len: i32) -> io::Result<()>
+    fn start_byte_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
     where
         W: Write;
-    fn start_int_array<W>(&mut self, writer: &mut W, len: i32) -> io::Result<()>
+    fn start_int_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
     where
         W: Write;
-    fn start_long_array<W>(&mut self, writer: &mut W, len: i32) -> io::Result<()>
+    fn start_long_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
     where
         W: Write;
-    fn start_list<W>(&mut self, writer: &mut W, len: i32, element_type: TagType) -> io::Result<()>
+    fn start_list<W>(
+        &mut self,
+        writer: &mut W,
+        len: i32,
+        element_type: TagType,
+    ) -> Result<(), Error>
     where
         W: Write;
-    fn start_element<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    fn start_element<W>(&mut self, writer: &mut W, first: bool) -> Result<(), Error>
     where
         W: Write;
-    fn end_sequence<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn end_sequence<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write;
 
-    fn start_compound<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn start_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write;
-    fn end_compound<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn end_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write;
 
-    fn start_entry<W>(&mut self, writer: &mut W, key: &[u8], value_type: TagType) -> io::Result<()>
+    fn start_entry<W>(
+        &mut self,
+        writer: &mut W,
+        key: &[u8],
+        value_type: TagType,
+    ) -> Result<(), Error>
     where
         W: Write;
-    fn end_entry<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn end_entry<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write;
 }
 
 pub(super) struct StringifiedFormatter {
     first: Vec<bool>,
+    packed: bool,
 }
 
 impl StringifiedFormatter {
     pub fn new() -> Self {
-        Self { first: Vec::new() }
+        Self {
+            first: Vec::new(),
+            packed: false,
+        }
+    }
+
+    /// Matches vanilla Minecraft's canonical SNBT output (e.g. what `/data
+    /// get` prints): omits the surrounding quotes on any key or string value
+    /// that's just `[A-Za-z0-9_.+-]+`, instead of always quoting.
+    pub fn packed() -> Self {
+        Self {
+            first: Vec::new(),
+            packed: true,
+        }
     }
 }
 
+/// Whether `value` can be written unquoted in SNBT: Minecraft's canonical
+/// writer only quotes strings/keys that don't match `[A-Za-z0-9_.+-]+`.
+fn is_unquoted_snbt_string(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'+' | b'-'))
+}
+
 impl Formatter for StringifiedFormatter {
-    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> io::Result<()>
+    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> Result<(), Error>
     where
         W: Write,
     {
-        write!(writer, "{}", if value { "true" } else { "false" })
+        writer.write_all(format!("{}", if value { "true" } else { "false" }).as_bytes())
     }
 
-    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> io::Result<()>
+    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> Result<(), Error>
     where
         W: Write,
     {
-        write!(writer, "{}b", value)
+        writer.write_all(format!("{}b", value).as_bytes())
     }
 
-    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> io::Result<()>
+    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> Result<(), Error>
     where
         W: Write,
     {
-        write!(writer, "{}s", value)
+        writer.write_all(format!("{}s", value).as_bytes())
     }
 
-    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> io::Result<()>
+    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> Result<(), Error>
     where
         W: Write,
     {
-        write!(writer, "{}", value)
+        writer.write_all(format!("{}", value).as_bytes())
     }
 
-    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> io::Result<()>
+    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> Result<(), Error>
     where
         W: Write,
     {
-        write!(writer, "{}l", value)
+        writer.write_all(format!("{}l", value).as_bytes())
     }
 
-    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> io::Result<()>
+    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> Result<(), Error>
     where
         W: Write,
     {
-        write!(writer, "{}f", value)
+        writer.write_all(format!("{}f", value).as_bytes())
     }
 
-    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> io::Result<()>
+    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> Result<(), Error>
     where
         W: Write,
     {
-        write!(writer, "{}d", value)
+        writer.write_all(format!("{}d", value).as_bytes())
     }
 
-    fn write_byte_array<W: Write>(&mut self, writer: &mut W, value: &[u8]) -> io::Result<()> {
-        write!(writer, "[B;")?;
+    fn write_byte_array<W: Write>(&mut self, writer: &mut W, value: &[u8]) -> Result<(), Error> {
+        writer.write_all(b"[B;")?;
         for (i, byte) in value.iter().enumerate() {
             if i != 0 {
-                write!(writer, ",")?;
+                writer.write_all(b",")?;
             }
-            write!(writer, "{}b", byte)?;
+            writer.write_all(format!("{}b", byte).as_bytes())?;
         }
-        write!(writer, "]")
+        writer.write_all(b"]")
     }
 
-    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> io::Result<()>
+    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> Result<(), Error>
     where
         W: Write,
     {
-        write!(writer, "\"{}\"", value.escape_debug())
+        if self.packed && is_unquoted_snbt_string(value) {
+            return writer.write_all(value.as_bytes());
+        }
+        writer.write_all(format!("\"{}\"", value.escape_debug()).as_bytes())
     }
 
-    fn start_byte_array<W>(&mut self, writer: &mut W, _len: i32) -> io::Result<()>
+    fn start_byte_array<W>(&mut self, writer: &mut W, _len: i32) -> Result<(), Error>
     where
         W: Write,
     {
         self.first.push(true);
-        write!(writer, "[B;")
+        writer.write_all(b"[B;")
     }
 
-    fn start_int_array<W>(&mut self, writer: &mut W, _len: i32) -> io::Result<()>
+    fn start_int_array<W>(&mut self, writer: &mut W, _len: i32) -> Result<(), Error>
     where
         W: Write,
     {
         self.first.push(true);
-        write!(writer, "[I;")
+        writer.write_all(b"[I;")
     }
 
-    fn start_long_array<W>(&mut self, writer: &mut W, _len: i32) -> io::Result<()>
+    fn start_long_array<W>(&mut self, writer: &mut W, _len: i32) -> Result<(), Error>
     where
         W: Write,
     {
         self.first.push(true);
-        write!(writer, "[L;")
+        writer.write_all(b"[L;")
     }
 
-    fn start_list<W>(&mut self, writer: &mut W, _len: i32, _element_type: TagType) -> io::Result<()>
+    fn start_list<W>(
+        &mut self,
+        writer: &mut W,
+        _len: i32,
+        _element_type: TagType,
+    ) -> Result<(), Error>
     where
         W: Write,
     {
         self.first.push(true);
-        write!(writer, "[")
+        writer.write_all(b"[")
     }
 
-    fn start_element<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    fn start_element<W>(&mut self, writer: &mut W, first: bool) -> Result<(), Error>
     where
         W: Write,
     {
         if !first {
-            write!(writer, ",")
+            writer.write_all(b",")
         } else {
             Ok(())
         }
     }
 
-    fn end_sequence<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn end_sequence<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write,
     {
         self.first.pop();
-        write!(writer, "]")
+        writer.write_all(b"]")
     }
 
-    fn start_compound<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn start_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write,
     {
         self.first.push(true);
-        write!(writer, "{{")
+        writer.write_all(format!("{{").as_bytes())
     }
-    fn end_compound<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn end_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write,
     {
         self.first.pop();
-        write!(writer, "}}")
+        writer.write_all(b"}")
+    }
+
+    fn start_entry<W>(
+        &mut self,
+        writer: &mut W,
+        key: &[u8],
+        _value_type: TagType,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        if let Some(first) = self.first.last_mut() {
+            if !*first {
+                writer.write_all(b",")?;
+            } else {
+                *first = false;
+            }
+        }
+        writer.write_all(key)?;
+        writer.write_all(b":")
+    }
+
+    fn end_entry<W>(&mut self, _writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+}
+
+/// Writes a newline followed by `n` copies of `indent`, mirroring serde_json's
+/// `PrettyFormatter` indentation helper.
+fn write_indent<W: Write>(writer: &mut W, indent: &[u8], n: usize) -> Result<(), Error> {
+    writer.write_all(b"\n")?;
+    for _ in 0..n {
+        writer.write_all(indent)?;
+    }
+    Ok(())
+}
+
+/// SNBT formatter that mirrors serde_json's `PrettyFormatter`: each compound
+/// entry or sequence element is placed on its own indented line, with empty
+/// compounds/lists/arrays kept on one line as `{}`/`[]`/`[B;]`.
+pub(super) struct PrettyStringifiedFormatter<'a> {
+    current_indent: usize,
+    // Whether the current level is still empty, i.e. hasn't seen a
+    // start_entry/start_element yet. Used at end_compound/end_sequence to
+    // decide whether to keep the closing brace on the opening line.
+    first: Vec<bool>,
+    indent: &'a [u8],
+}
+
+impl<'a> PrettyStringifiedFormatter<'a> {
+    pub fn new() -> Self {
+        Self::with_indent(b"  ")
+    }
+
+    pub fn with_indent(indent: &'a [u8]) -> Self {
+        Self {
+            current_indent: 0,
+            first: Vec::new(),
+            indent,
+        }
+    }
+}
+
+impl<'a> Formatter for PrettyStringifiedFormatter<'a> {
+    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(format!("{}", if value { "true" } else { "false" }).as_bytes())
+    }
+
+    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(format!("{}b", value).as_bytes())
+    }
+
+    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(format!("{}s", value).as_bytes())
+    }
+
+    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(format!("{}", value).as_bytes())
+    }
+
+    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(format!("{}l", value).as_bytes())
+    }
+
+    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(format!("{}f", value).as_bytes())
+    }
+
+    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(format!("{}d", value).as_bytes())
+    }
+
+    fn write_byte_array<W: Write>(&mut self, writer: &mut W, value: &[u8]) -> Result<(), Error> {
+        if value.is_empty() {
+            return writer.write_all(b"[B;]");
+        }
+
+        writer.write_all(b"[B;")?;
+        self.current_indent += 1;
+        for (i, byte) in value.iter().enumerate() {
+            if i != 0 {
+                writer.write_all(b",")?;
+            }
+            write_indent(writer, self.indent, self.current_indent)?;
+            writer.write_all(format!("{}b", byte).as_bytes())?;
+        }
+        self.current_indent -= 1;
+        write_indent(writer, self.indent, self.current_indent)?;
+        writer.write_all(b"]")
+    }
+
+    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(format!("\"{}\"", value.escape_debug()).as_bytes())
+    }
+
+    fn start_byte_array<W>(&mut self, writer: &mut W, _len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.first.push(true);
+        self.current_indent += 1;
+        writer.write_all(b"[B;")
+    }
+
+    fn start_int_array<W>(&mut self, writer: &mut W, _len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.first.push(true);
+        self.current_indent += 1;
+        writer.write_all(b"[I;")
+    }
+
+    fn start_long_array<W>(&mut self, writer: &mut W, _len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.first.push(true);
+        self.current_indent += 1;
+        writer.write_all(b"[L;")
+    }
+
+    fn start_list<W>(
+        &mut self,
+        writer: &mut W,
+        _len: i32,
+        _element_type: TagType,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.first.push(true);
+        self.current_indent += 1;
+        writer.write_all(b"[")
+    }
+
+    fn start_element<W>(&mut self, writer: &mut W, first: bool) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        if let Some(top) = self.first.last_mut() {
+            *top = false;
+        }
+        if !first {
+            writer.write_all(b",")?;
+        }
+        write_indent(writer, self.indent, self.current_indent)
     }
 
-    fn start_entry<W>(&mut self, writer: &mut W, key: &[u8], _value_type: TagType) -> io::Result<()>
+    fn end_sequence<W>(&mut self, writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.current_indent -= 1;
+        if !self.first.pop().unwrap_or(true) {
+            write_indent(writer, self.indent, self.current_indent)?;
+        }
+        writer.write_all(b"]")
+    }
+
+    fn start_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.first.push(true);
+        self.current_indent += 1;
+        writer.write_all(format!("{{").as_bytes())
+    }
+
+    fn end_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        self.current_indent -= 1;
+        if !self.first.pop().unwrap_or(true) {
+            write_indent(writer, self.indent, self.current_indent)?;
+        }
+        writer.write_all(b"}")
+    }
+
+    fn start_entry<W>(
+        &mut self,
+        writer: &mut W,
+        key: &[u8],
+        _value_type: TagType,
+    ) -> Result<(), Error>
     where
         W: Write,
     {
         if let Some(first) = self.first.last_mut() {
             if !*first {
-                write!(writer, ",")?;
+                writer.write_all(b",")?;
             } else {
                 *first = false;
             }
         }
+        write_indent(writer, self.indent, self.current_indent)?;
         writer.write_all(key)?;
-        write!(writer, ":")
+        writer.write_all(b": ")
     }
 
-    fn end_entry<W>(&mut self, _writer: &mut W) -> io::Result<()>
+    fn end_entry<W>(&mut self, _writer: &mut W) -> Result<(), Error>
     where
         W: Write,
     {
@@ -243,56 +515,56 @@ impl BinaryFormatter {
 }
 
 impl Formatter for BinaryFormatter {
-    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> io::Result<()>
+    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&[if value { 1 } else { 0 }])
     }
 
-    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> io::Result<()>
+    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&[value as u8])
     }
 
-    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> io::Result<()>
+    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&value.to_be_bytes())
     }
 
-    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> io::Result<()>
+    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&value.to_be_bytes())
     }
 
-    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> io::Result<()>
+    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&value.to_be_bytes())
     }
 
-    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> io::Result<()>
+    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&value.to_be_bytes())
     }
 
-    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> io::Result<()>
+    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&value.to_be_bytes())
     }
 
-    fn write_byte_array<W>(&mut self, writer: &mut W, value: &[u8]) -> io::Result<()>
+    fn write_byte_array<W>(&mut self, writer: &mut W, value: &[u8]) -> Result<(), Error>
     where
         W: Write,
     {
@@ -300,7 +572,7 @@ impl Formatter for BinaryFormatter {
         writer.write_all(value)
     }
 
-    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> io::Result<()>
+    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> Result<(), Error>
     where
         W: Write,
     {
@@ -309,28 +581,33 @@ impl Formatter for BinaryFormatter {
         writer.write_all(cesu8::to_java_cesu8(value).as_ref())
     }
 
-    fn start_byte_array<W>(&mut self, writer: &mut W, len: i32) -> io::Result<()>
+    fn start_byte_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&len.to_be_bytes())
     }
 
-    fn start_int_array<W>(&mut self, writer: &mut W, len: i32) -> io::Result<()>
+    fn start_int_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&len.to_be_bytes())
     }
 
-    fn start_long_array<W>(&mut self, writer: &mut W, len: i32) -> io::Result<()>
+    fn start_long_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&len.to_be_bytes())
     }
 
-    fn start_list<W>(&mut self, writer: &mut W, len: i32, element_type: TagType) -> io::Result<()>
+    fn start_list<W>(
+        &mut self,
+        writer: &mut W,
+        len: i32,
+        element_type: TagType,
+    ) -> Result<(), Error>
     where
         W: Write,
     {
@@ -346,21 +623,21 @@ impl Formatter for BinaryFormatter {
         writer.write_all(&len.to_be_bytes())
     }
 
-    fn start_element<W>(&mut self, _writer: &mut W, _first: bool) -> io::Result<()>
+    fn start_element<W>(&mut self, _writer: &mut W, _first: bool) -> Result<(), Error>
     where
         W: Write,
     {
         Ok(())
     }
 
-    fn end_sequence<W>(&mut self, _writer: &mut W) -> io::Result<()>
+    fn end_sequence<W>(&mut self, _writer: &mut W) -> Result<(), Error>
     where
         W: Write,
     {
         Ok(())
     }
 
-    fn start_compound<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn start_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write,
     {
@@ -375,14 +652,434 @@ impl Formatter for BinaryFormatter {
         }
     }
 
-    fn end_compound<W>(&mut self, writer: &mut W) -> io::Result<()>
+    fn end_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&[TagType::End as u8])
+    }
+
+    fn start_entry<W>(
+        &mut self,
+        writer: &mut W,
+        key: &[u8],
+        value_type: TagType,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&[value_type as u8])?;
+        writer.write_all(key)
+    }
+
+    fn end_entry<W>(&mut self, _writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+}
+
+/// Writes an unsigned value as a variable-length integer: 7 bits of value per
+/// byte, least significant group first, with the high bit of each byte set
+/// except on the last one.
+fn write_unsigned_varint<W>(writer: &mut W, mut value: u32) -> Result<(), Error>
+where
+    W: Write,
+{
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Same as [`write_unsigned_varint`], but for 64-bit values.
+fn write_unsigned_varlong<W>(writer: &mut W, mut value: u64) -> Result<(), Error>
+where
+    W: Write,
+{
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Writes a signed value as a ZigZag-encoded [`write_unsigned_varint`], so
+/// small negative numbers are as compact as small positive ones.
+fn write_zigzag_varint<W>(writer: &mut W, value: i32) -> Result<(), Error>
+where
+    W: Write,
+{
+    write_unsigned_varint(writer, ((value << 1) ^ (value >> 31)) as u32)
+}
+
+/// Same as [`write_zigzag_varint`], but for 64-bit values.
+fn write_zigzag_varlong<W>(writer: &mut W, value: i64) -> Result<(), Error>
+where
+    W: Write,
+{
+    write_unsigned_varlong(writer, ((value << 1) ^ (value >> 63)) as u64)
+}
+
+/// Writes Bedrock Edition's little-endian NBT file format: structurally
+/// identical to Java's [`BinaryFormatter`], but every multi-byte field,
+/// including length prefixes, is little-endian instead of big-endian.
+pub(super) struct LittleEndianFormatter {
+    top_level: bool,
+}
+
+impl LittleEndianFormatter {
+    pub fn new() -> Self {
+        Self { top_level: true }
+    }
+}
+
+impl Formatter for LittleEndianFormatter {
+    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&[if value { 1 } else { 0 }])
+    }
+
+    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&[value as u8])
+    }
+
+    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_byte_array<W>(&mut self, writer: &mut W, value: &[u8]) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&(value.len() as i32).to_le_bytes())?;
+        writer.write_all(value)
+    }
+
+    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        let encoded = cesu8::to_java_cesu8(value);
+        let len = u16::try_from(encoded.len()).map_err(|_| Error::StringTooBig)?;
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(encoded.as_ref())
+    }
+
+    fn start_byte_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&len.to_le_bytes())
+    }
+
+    fn start_int_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&len.to_le_bytes())
+    }
+
+    fn start_long_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&len.to_le_bytes())
+    }
+
+    fn start_list<W>(
+        &mut self,
+        writer: &mut W,
+        len: i32,
+        element_type: TagType,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        if self.top_level {
+            // Minecraft only generates files with a Compound or List at the top
+            // level, so assume this is a List and specify the tag type and name
+            // it the empty string.
+            self.top_level = false;
+            writer.write_all(&[TagType::List as u8])?;
+            self.write_string(writer, "")?;
+        }
+
+        writer.write_all(&[element_type as u8])?;
+        writer.write_all(&len.to_le_bytes())
+    }
+
+    fn start_element<W>(&mut self, _writer: &mut W, _first: bool) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+
+    fn end_sequence<W>(&mut self, _writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+
+    fn start_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        if self.top_level {
+            // Minecraft only generates files with a Compound or List at the top
+            // level, so assume this is a Compound and specify the tag type and
+            // name it the empty string.
+            self.top_level = false;
+            writer.write_all(&[TagType::Compound as u8])?;
+            self.write_string(writer, "")
+        } else {
+            Ok(())
+        }
+    }
+
+    fn end_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&[TagType::End as u8])
+    }
+
+    fn start_entry<W>(
+        &mut self,
+        writer: &mut W,
+        key: &[u8],
+        value_type: TagType,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&[value_type as u8])?;
+        writer.write_all(key)
+    }
+
+    fn end_entry<W>(&mut self, _writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+}
+
+/// Writes Bedrock Edition's network NBT format, used to send NBT over the
+/// Bedrock protocol: the same shape as [`LittleEndianFormatter`], but `int`
+/// payloads and list/array lengths are ZigZag varints, `long` payloads are
+/// ZigZag varlongs, and the string length prefix is an unsigned varint of the
+/// Modified UTF-8 payload's byte count instead of a fixed `u16`. Tag type bytes and floating
+/// point payloads are unaffected, staying as little-endian raw bytes.
+pub(super) struct NetworkFormatter {
+    top_level: bool,
+}
+
+impl NetworkFormatter {
+    pub fn new() -> Self {
+        Self { top_level: true }
+    }
+}
+
+impl Formatter for NetworkFormatter {
+    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&[if value { 1 } else { 0 }])
+    }
+
+    fn write_byte<W>(&mut self, writer: &mut W, value: i8) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&[value as u8])
+    }
+
+    fn write_short<W>(&mut self, writer: &mut W, value: i16) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_int<W>(&mut self, writer: &mut W, value: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        write_zigzag_varint(writer, value)
+    }
+
+    fn write_long<W>(&mut self, writer: &mut W, value: i64) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        write_zigzag_varlong(writer, value)
+    }
+
+    fn write_float<W>(&mut self, writer: &mut W, value: f32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_double<W>(&mut self, writer: &mut W, value: f64) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_byte_array<W>(&mut self, writer: &mut W, value: &[u8]) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        write_zigzag_varint(writer, value.len() as i32)?;
+        writer.write_all(value)
+    }
+
+    fn write_string<W>(&mut self, writer: &mut W, value: &str) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        let encoded = cesu8::to_java_cesu8(value);
+        write_unsigned_varint(writer, encoded.len() as u32)?;
+        writer.write_all(encoded.as_ref())
+    }
+
+    fn start_byte_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        write_zigzag_varint(writer, len)
+    }
+
+    fn start_int_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        write_zigzag_varint(writer, len)
+    }
+
+    fn start_long_array<W>(&mut self, writer: &mut W, len: i32) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        write_zigzag_varint(writer, len)
+    }
+
+    fn start_list<W>(
+        &mut self,
+        writer: &mut W,
+        len: i32,
+        element_type: TagType,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        if self.top_level {
+            // Minecraft only generates files with a Compound or List at the top
+            // level, so assume this is a List and specify the tag type and name
+            // it the empty string.
+            self.top_level = false;
+            writer.write_all(&[TagType::List as u8])?;
+            self.write_string(writer, "")?;
+        }
+
+        writer.write_all(&[element_type as u8])?;
+        write_zigzag_varint(writer, len)
+    }
+
+    fn start_element<W>(&mut self, _writer: &mut W, _first: bool) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+
+    fn end_sequence<W>(&mut self, _writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        Ok(())
+    }
+
+    fn start_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
+    where
+        W: Write,
+    {
+        if self.top_level {
+            // Minecraft only generates files with a Compound or List at the top
+            // level, so assume this is a Compound and specify the tag type and
+            // name it the empty string.
+            self.top_level = false;
+            writer.write_all(&[TagType::Compound as u8])?;
+            self.write_string(writer, "")
+        } else {
+            Ok(())
+        }
+    }
+
+    fn end_compound<W>(&mut self, writer: &mut W) -> Result<(), Error>
     where
         W: Write,
     {
         writer.write_all(&[TagType::End as u8])
     }
 
-    fn start_entry<W>(&mut self, writer: &mut W, key: &[u8], value_type: TagType) -> io::Result<()>
+    fn start_entry<W>(
+        &mut self,
+        writer: &mut W,
+        key: &[u8],
+        value_type: TagType,
+    ) -> Result<(), Error>
     where
         W: Write,
     {
@@ -390,7 +1087,7 @@ impl Formatter for BinaryFormatter {
         writer.write_all(key)
     }
 
-    fn end_entry<W>(&mut self, _writer: &mut W) -> io::Result<()>
+    fn end_entry<W>(&mut self, _writer: &mut W) -> Result<(), Error>
     where
         W: Write,
     {
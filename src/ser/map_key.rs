@@ -1,4 +1,4 @@
-use std::io::Write;
+use super::write::Write;
 
 use serde::{
     ser::{self, Impossible},
@@ -1,4 +1,4 @@
-use std::io::{Cursor, Write};
+use std::io::Cursor;
 
 use serde::ser::{
     self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
@@ -9,18 +9,62 @@ use crate::nbt::TagType;
 
 use self::{
     error::Error,
-    formatter::{BinaryFormatter, Formatter, StringifiedFormatter},
+    formatter::{
+        BinaryFormatter, Formatter, LittleEndianFormatter, NetworkFormatter,
+        PrettyStringifiedFormatter, StringifiedFormatter,
+    },
     tag_type::to_tag_type,
+    write::Write,
 };
 
 mod error;
 mod formatter;
 mod map_key;
 mod tag_type;
+pub mod write;
 
 struct Serializer<W, F = BinaryFormatter> {
     writer: W,
     formatter: F,
+    // Set while serializing the payload of a sentinel newtype struct (see
+    // crate::nbt's *_ARRAY_TOKEN docs) so serialize_bytes knows whether to
+    // write a plain ByteArray or decode the bytes back into an IntArray or
+    // LongArray.
+    array_hint: Option<TagType>,
+    config: Config,
+}
+
+/// Options controlling how the serializer handles Rust values that don't map
+/// onto NBT losslessly by default. Build one with [`Config::new`] and chain
+/// the setters, following serde_cbor's `enum_as_map`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Config {
+    enum_as_map: bool,
+    unsigned_wrap: bool,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// When set, externally-tagged enum variants that carry a value (newtype
+    /// and struct variants) are serialized as a single-key compound
+    /// `{variant: value}` instead of silently discarding the variant name.
+    /// Unit variants are unaffected; they already serialize as the variant
+    /// name.
+    pub fn enum_as_map(mut self, enum_as_map: bool) -> Self {
+        self.enum_as_map = enum_as_map;
+        self
+    }
+
+    /// When set, `u32`/`u64` values that don't fit into `i32`/`i64` are
+    /// reinterpreted bit-for-bit as the signed `int`/`long` NBT tag instead
+    /// of failing with [`Error::UnsignedTooBig`].
+    pub fn unsigned_wrap(mut self, unsigned_wrap: bool) -> Self {
+        self.unsigned_wrap = unsigned_wrap;
+        self
+    }
 }
 
 pub fn to_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
@@ -28,7 +72,17 @@ where
     W: Write,
     T: Serialize,
 {
-    let mut serializer = Serializer::new(writer);
+    to_writer_with_config(writer, value, Config::default())
+}
+
+/// Same as [`to_writer`], but with explicit [`Config`] for lossy conversions
+/// that are normally rejected.
+pub fn to_writer_with_config<W, T>(writer: W, value: &T, config: Config) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_config(writer, config);
     value.serialize(&mut serializer)
 }
 
@@ -37,18 +91,123 @@ where
     W: Write,
     T: Serialize,
 {
-    let mut serializer = Serializer::with_formatter(writer, StringifiedFormatter::new());
+    to_snbt_writer_with_config(writer, value, Config::default())
+}
+
+/// Same as [`to_snbt_writer`], but with explicit [`Config`] for lossy
+/// conversions that are normally rejected.
+pub fn to_snbt_writer_with_config<W, T>(writer: W, value: &T, config: Config) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer =
+        Serializer::with_formatter_and_config(writer, StringifiedFormatter::new(), config);
+    value.serialize(&mut serializer)
+}
+
+/// Serializes a value as SNBT, indenting nested compounds/lists/arrays one
+/// level per line (two spaces) instead of the compact single-line form
+/// `to_snbt_writer` produces.
+pub fn to_pretty_snbt_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_formatter(writer, PrettyStringifiedFormatter::new());
+    value.serialize(&mut serializer)
+}
+
+/// Serializes a value as Mojang's textual NBT (SNBT) representation, the form
+/// used by commands and datapacks, e.g. `{"Name":"test","Count":1b}`.
+pub fn to_snbt<T>(value: &T) -> Result<String, Error>
+where
+    T: Serialize,
+{
+    let mut buffer = Vec::new();
+    to_snbt_writer(&mut buffer, value)?;
+    Ok(String::from_utf8(buffer).expect("SNBT output is always valid UTF-8"))
+}
+
+/// Serializes a value as SNBT in Minecraft's canonical/"packed" form,
+/// omitting quotes on keys and string values that don't need them, the same
+/// output the game's `/data get` command produces, e.g. `{name:test,age:40s}`.
+pub fn to_snbt_packed_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_formatter(writer, StringifiedFormatter::packed());
+    value.serialize(&mut serializer)
+}
+
+/// Same as [`to_snbt`], but in the canonical/"packed" form [`to_snbt_packed_writer`]
+/// produces.
+pub fn to_snbt_packed<T>(value: &T) -> Result<String, Error>
+where
+    T: Serialize,
+{
+    let mut buffer = Vec::new();
+    to_snbt_packed_writer(&mut buffer, value)?;
+    Ok(String::from_utf8(buffer).expect("SNBT output is always valid UTF-8"))
+}
+
+/// Serializes a value as Bedrock Edition's little-endian NBT, the form used
+/// by Bedrock's world and entity save files.
+pub fn to_le_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_formatter(writer, LittleEndianFormatter::new());
+    value.serialize(&mut serializer)
+}
+
+/// Serializes a value as Bedrock Edition's network NBT, the varint-encoded
+/// form sent over the Bedrock protocol.
+pub fn to_network_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_formatter(writer, NetworkFormatter::new());
     value.serialize(&mut serializer)
 }
 
+/// Serializes a value as binary NBT into a freshly-allocated `Vec<u8>`.
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: Serialize,
+{
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, value)?;
+    Ok(buffer)
+}
+
+/// Serializes a value as SNBT into a freshly-allocated `Vec<u8>`.
+pub fn to_snbt_vec<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: Serialize,
+{
+    let mut buffer = Vec::new();
+    to_snbt_writer(&mut buffer, value)?;
+    Ok(buffer)
+}
+
 impl<W> Serializer<W>
 where
     W: Write,
 {
     fn new(writer: W) -> Self {
+        Self::with_config(writer, Config::default())
+    }
+
+    fn with_config(writer: W, config: Config) -> Self {
         Serializer {
             writer,
             formatter: BinaryFormatter::new(),
+            array_hint: None,
+            config,
         }
     }
 }
@@ -59,7 +218,16 @@ where
     F: Formatter,
 {
     fn with_formatter(writer: W, formatter: F) -> Self {
-        Serializer { writer, formatter }
+        Self::with_formatter_and_config(writer, formatter, Config::default())
+    }
+
+    fn with_formatter_and_config(writer: W, formatter: F, config: Config) -> Self {
+        Serializer {
+            writer,
+            formatter,
+            array_hint: None,
+            config,
+        }
     }
 }
 
@@ -114,17 +282,21 @@ where
     }
 
     fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-        Ok(self.formatter.write_int(
-            &mut self.writer,
-            i32::try_from(v).map_err(|_| Error::UnsignedTooBig)?,
-        )?)
+        let v = if self.config.unsigned_wrap {
+            v as i32
+        } else {
+            i32::try_from(v).map_err(|_| Error::UnsignedTooBig)?
+        };
+        Ok(self.formatter.write_int(&mut self.writer, v)?)
     }
 
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-        Ok(self.formatter.write_long(
-            &mut self.writer,
-            i64::try_from(v).map_err(|_| Error::UnsignedTooBig)?,
-        )?)
+        let v = if self.config.unsigned_wrap {
+            v as i64
+        } else {
+            i64::try_from(v).map_err(|_| Error::UnsignedTooBig)?
+        };
+        Ok(self.formatter.write_long(&mut self.writer, v)?)
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
@@ -148,7 +320,36 @@ where
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        Ok(self.formatter.write_byte_array(&mut self.writer, v)?)
+        match self.array_hint.take() {
+            Some(TagType::IntArray) => {
+                let len = (v.len() / 4) as i32;
+                self.formatter.start_int_array(&mut self.writer, len)?;
+                for (i, chunk) in v.chunks_exact(4).enumerate() {
+                    self.formatter.start_element(&mut self.writer, i == 0)?;
+                    self.formatter.write_int(
+                        &mut self.writer,
+                        i32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]),
+                    )?;
+                }
+                Ok(self.formatter.end_sequence(&mut self.writer)?)
+            }
+            Some(TagType::LongArray) => {
+                let len = (v.len() / 8) as i32;
+                self.formatter.start_long_array(&mut self.writer, len)?;
+                for (i, chunk) in v.chunks_exact(8).enumerate() {
+                    self.formatter.start_element(&mut self.writer, i == 0)?;
+                    self.formatter.write_long(
+                        &mut self.writer,
+                        i64::from_be_bytes([
+                            chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6],
+                            chunk[7],
+                        ]),
+                    )?;
+                }
+                Ok(self.formatter.end_sequence(&mut self.writer)?)
+            }
+            _ => Ok(self.formatter.write_byte_array(&mut self.writer, v)?),
+        }
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
@@ -178,20 +379,46 @@ where
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        value.serialize(self)
+        let hint = match name {
+            crate::nbt::BYTE_ARRAY_TOKEN => Some(TagType::ByteArray),
+            crate::nbt::INT_ARRAY_TOKEN => Some(TagType::IntArray),
+            crate::nbt::LONG_ARRAY_TOKEN => Some(TagType::LongArray),
+            _ => None,
+        };
+
+        if let Some(hint) = hint {
+            let previous = self.array_hint.replace(hint);
+            let result = value.serialize(&mut *self);
+            self.array_hint = previous;
+            result
+        } else {
+            value.serialize(self)
+        }
     }
 
     fn serialize_newtype_variant<T: ?Sized + Serialize>(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        value.serialize(self)
+        if !self.config.enum_as_map {
+            return value.serialize(self);
+        }
+
+        self.formatter.start_compound(&mut self.writer)?;
+        let value_type = to_tag_type(value)?;
+        let mut key = Cursor::new(Vec::new());
+        variant.serialize(map_key::Serializer::new(&mut key, &mut self.formatter))?;
+        self.formatter
+            .start_entry(&mut self.writer, key.get_ref(), value_type)?;
+        value.serialize(&mut *self)?;
+        self.formatter.end_entry(&mut self.writer)?;
+        Ok(self.formatter.end_compound(&mut self.writer)?)
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
@@ -218,6 +445,11 @@ where
         self.serialize_seq(Some(len))
     }
 
+    // `enum_as_map` isn't applied here: unlike a newtype or struct variant,
+    // a tuple variant's NBT tag type (List vs. one of the *Array types)
+    // isn't known until its first field is serialized, by which point the
+    // entry's tag-type byte would already need to have been written. Tuple
+    // variants are serialized the same way regardless of `enum_as_map`.
     fn serialize_tuple_variant(
         self,
         _name: &'static str,
@@ -245,17 +477,29 @@ where
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        self.serialize_map(Some(len))
+        if !self.config.enum_as_map {
+            return self.serialize_map(Some(len));
+        }
+
+        self.formatter.start_compound(&mut self.writer)?;
+        let mut key = Cursor::new(Vec::new());
+        variant.serialize(map_key::Serializer::new(&mut key, &mut self.formatter))?;
+        self.formatter
+            .start_entry(&mut self.writer, key.get_ref(), TagType::Compound)?;
+        self.formatter.start_compound(&mut self.writer)?;
+        Ok(MapSerializer::wrapped(self))
     }
 }
 
 struct SeqSerializer<'a, W, F> {
     serializer: &'a mut Serializer<W, F>,
     len: i32,
-    first: bool,
+    // The tag type of the first element, checked against every later element
+    // so a list can't silently mix types NBT has no way to represent.
+    element_type: Option<TagType>,
 }
 
 impl<'a, W, F> SeqSerializer<'a, W, F> {
@@ -263,7 +507,7 @@ impl<'a, W, F> SeqSerializer<'a, W, F> {
         SeqSerializer {
             serializer,
             len,
-            first: true,
+            element_type: None,
         }
     }
 }
@@ -277,10 +521,16 @@ where
     type Error = Error;
 
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        let first = self.first;
-        if self.first {
-            self.first = false;
-            match to_tag_type(value)? {
+        let tag_type = to_tag_type(value)?;
+        let first = self.element_type.is_none();
+
+        if let Some(element_type) = self.element_type {
+            if element_type != tag_type {
+                return Err(Error::HeterogeneousSequence);
+            }
+        } else {
+            self.element_type = Some(tag_type);
+            match tag_type {
                 TagType::Byte => self
                     .serializer
                     .formatter
@@ -308,6 +558,19 @@ where
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
+        // An empty sequence never calls serialize_element, so start_list
+        // above never ran and the list's element-type/length header hasn't
+        // been written yet. NBT still requires one even for a 0-length list,
+        // so write it now with TAG_End as the element type, matching the
+        // convention vanilla Minecraft uses for empty lists.
+        if self.element_type.is_none() {
+            self.serializer.formatter.start_list(
+                &mut self.serializer.writer,
+                self.len,
+                TagType::End,
+            )?;
+        }
+
         Ok(self
             .serializer
             .formatter
@@ -369,6 +632,10 @@ where
 struct MapSerializer<'a, W, F> {
     serializer: &'a mut Serializer<W, F>,
     key: Cursor<Vec<u8>>,
+    // Set when this compound is itself the value of an `enum_as_map`-wrapped
+    // struct variant's single entry, so `end` also closes that outer entry
+    // and compound once this one is done.
+    wrapped: bool,
 }
 
 impl<'a, W, F> MapSerializer<'a, W, F> {
@@ -376,6 +643,15 @@ impl<'a, W, F> MapSerializer<'a, W, F> {
         MapSerializer {
             serializer,
             key: Cursor::new(Vec::new()),
+            wrapped: false,
+        }
+    }
+
+    fn wrapped(serializer: &'a mut Serializer<W, F>) -> Self {
+        MapSerializer {
+            serializer,
+            key: Cursor::new(Vec::new()),
+            wrapped: true,
         }
     }
 }
@@ -416,10 +692,18 @@ where
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(self
-            .serializer
+        self.serializer
             .formatter
-            .end_compound(&mut self.serializer.writer)?)
+            .end_compound(&mut self.serializer.writer)?;
+        if self.wrapped {
+            self.serializer
+                .formatter
+                .end_entry(&mut self.serializer.writer)?;
+            self.serializer
+                .formatter
+                .end_compound(&mut self.serializer.writer)?;
+        }
+        Ok(())
     }
 }
 
@@ -467,6 +751,8 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
 
     use serde::Serialize;
@@ -554,4 +840,295 @@ mod tests {
             generate_example_snbt(),
         );
     }
+
+    #[test]
+    fn test_to_snbt() {
+        let example_nbt = generate_example();
+        assert_eq!(to_snbt(&example_nbt).unwrap(), generate_example_snbt());
+    }
+
+    #[test]
+    fn test_to_snbt_packed() {
+        let example_nbt = generate_example();
+        assert_eq!(
+            to_snbt_packed(&example_nbt).unwrap(),
+            "{name:\"test nbt\",age:40s,inventory:[{name:test,count:1},{name:test2,count:2}]}",
+        );
+    }
+
+    #[test]
+    fn test_to_snbt_packed_quotes_when_necessary() {
+        #[derive(Serialize)]
+        struct Oddballs {
+            #[serde(rename = "has space")]
+            has_space: String,
+            empty: String,
+        }
+
+        let value = Oddballs {
+            has_space: "has space".to_string(),
+            empty: String::new(),
+        };
+        assert_eq!(
+            to_snbt_packed(&value).unwrap(),
+            "{\"has space\":\"has space\",empty:\"\"}",
+        );
+    }
+
+    #[test]
+    fn test_pretty_snbt() {
+        let example_nbt = generate_example();
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        to_pretty_snbt_writer(&mut buffer, &example_nbt).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(buffer.get_ref()),
+            concat!(
+                "{\n",
+                "  \"name\": \"test nbt\",\n",
+                "  \"age\": 40s,\n",
+                "  \"inventory\": [\n",
+                "    {\n",
+                "      \"name\": \"test\",\n",
+                "      \"count\": 1\n",
+                "    },\n",
+                "    {\n",
+                "      \"name\": \"test2\",\n",
+                "      \"count\": 2\n",
+                "    }\n",
+                "  ]\n",
+                "}",
+            ),
+        );
+    }
+
+    #[test]
+    fn test_pretty_snbt_empty_compound() {
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        to_pretty_snbt_writer(&mut buffer, &HashMap::<String, i32>::new()).unwrap();
+        assert_eq!(String::from_utf8_lossy(buffer.get_ref()), "{}");
+    }
+
+    fn generate_example_le_nbt() -> Vec<u8> {
+        vec![
+            0x0a, 0x00, 0x00, // compound, empty name
+            0x08, // string
+            0x04, 0x00, 0x6e, 0x61, 0x6d, 0x65, // name "name"
+            0x08, 0x00, 0x74, 0x65, 0x73, 0x74, 0x20, 0x6e, 0x62, 0x74, // value "test nbt"
+            0x02, // short
+            0x03, 0x00, 0x61, 0x67, 0x65, // name "age"
+            0x28, 0x00, // value 40
+            0x09, 0x00, 0x69, 0x6e, 0x76, 0x65, 0x6e, 0x74, 0x6f, 0x72,
+            0x79, // name "inventory"
+            0x0a, 0x02, 0x00, 0x00, 0x00, // list of type compound, len 2
+            0x08, // string
+            0x04, 0x00, 0x6e, 0x61, 0x6d, 0x65, // name "name"
+            0x04, 0x00, 0x74, 0x65, 0x73, 0x74, // value "test"
+            0x03, // int
+            0x05, 0x00, 0x63, 0x6f, 0x75, 0x6e, 0x74, // name "count"
+            0x01, 0x00, 0x00, 0x00, // value 1
+            0x00, // end tag
+            0x08, // string
+            0x04, 0x00, 0x6e, 0x61, 0x6d, 0x65, // name "name"
+            0x05, 0x00, 0x74, 0x65, 0x73, 0x74, 0x32, // value "test2"
+            0x03, // int
+            0x05, 0x00, 0x63, 0x6f, 0x75, 0x6e, 0x74, // name "count"
+            0x02, 0x00, 0x00, 0x00, // value 2
+            0x00, // end tag
+            0x00, // end tag
+        ]
+    }
+
+    fn generate_example_network_nbt() -> Vec<u8> {
+        vec![
+            0x0a, 0x00, // compound, empty name
+            0x08, // string
+            0x04, 0x6e, 0x61, 0x6d, 0x65, // name "name"
+            0x08, 0x74, 0x65, 0x73, 0x74, 0x20, 0x6e, 0x62, 0x74, // value "test nbt"
+            0x02, // short
+            0x03, 0x61, 0x67, 0x65, // name "age"
+            0x28, 0x00, // value 40
+            0x09, 0x69, 0x6e, 0x76, 0x65, 0x6e, 0x74, 0x6f, 0x72, 0x79, // name "inventory"
+            0x0a, 0x04, // list of type compound, zigzag varint len 2
+            0x08, // string
+            0x04, 0x6e, 0x61, 0x6d, 0x65, // name "name"
+            0x04, 0x74, 0x65, 0x73, 0x74, // value "test"
+            0x03, // int
+            0x05, 0x63, 0x6f, 0x75, 0x6e, 0x74, // name "count"
+            0x02, // zigzag varint value 1
+            0x00, // end tag
+            0x08, // string
+            0x04, 0x6e, 0x61, 0x6d, 0x65, // name "name"
+            0x05, 0x74, 0x65, 0x73, 0x74, 0x32, // value "test2"
+            0x03, // int
+            0x05, 0x63, 0x6f, 0x75, 0x6e, 0x74, // name "count"
+            0x04, // zigzag varint value 2
+            0x00, // end tag
+            0x00, // end tag
+        ]
+    }
+
+    #[test]
+    fn test_le_nbt() {
+        let example_nbt = generate_example();
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        to_le_writer(&mut buffer, &example_nbt).unwrap();
+        assert_eq!(buffer.get_ref(), &generate_example_le_nbt());
+    }
+
+    #[test]
+    fn test_to_vec() {
+        let example_nbt = generate_example();
+        assert_eq!(to_vec(&example_nbt).unwrap(), generate_example_nbt());
+    }
+
+    #[test]
+    fn test_to_snbt_vec() {
+        let example_nbt = generate_example();
+        assert_eq!(
+            String::from_utf8(to_snbt_vec(&example_nbt).unwrap()).unwrap(),
+            generate_example_snbt(),
+        );
+    }
+
+    #[test]
+    fn test_network_nbt() {
+        let example_nbt = generate_example();
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        to_network_writer(&mut buffer, &example_nbt).unwrap();
+        assert_eq!(buffer.get_ref(), &generate_example_network_nbt());
+    }
+
+    #[derive(Serialize)]
+    enum Event {
+        Ping,
+        Message(String),
+        Move { x: i32, y: i32 },
+    }
+
+    #[test]
+    fn test_enum_as_map_disabled_drops_variant() {
+        assert_eq!(to_snbt(&Event::Ping).unwrap(), "\"Ping\"");
+        assert_eq!(
+            to_snbt(&Event::Message("hi".to_string())).unwrap(),
+            "\"hi\""
+        );
+        assert_eq!(
+            to_snbt(&Event::Move { x: 1, y: 2 }).unwrap(),
+            "{\"x\":1,\"y\":2}"
+        );
+    }
+
+    fn to_snbt_with_enum_as_map<T: Serialize>(value: &T) -> String {
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        to_snbt_writer_with_config(&mut buffer, value, Config::new().enum_as_map(true)).unwrap();
+        String::from_utf8(buffer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_enum_as_map_unit_variant_unaffected() {
+        assert_eq!(to_snbt_with_enum_as_map(&Event::Ping), "\"Ping\"");
+    }
+
+    #[test]
+    fn test_enum_as_map_newtype_variant() {
+        assert_eq!(
+            to_snbt_with_enum_as_map(&Event::Message("hi".to_string())),
+            "{\"Message\":\"hi\"}"
+        );
+    }
+
+    #[test]
+    fn test_enum_as_map_struct_variant() {
+        assert_eq!(
+            to_snbt_with_enum_as_map(&Event::Move { x: 1, y: 2 }),
+            "{\"Move\":{\"x\":1,\"y\":2}}"
+        );
+    }
+
+    #[test]
+    fn test_unsigned_wrap_disabled_errors() {
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        assert!(matches!(
+            to_writer(&mut buffer, &u32::MAX),
+            Err(Error::UnsignedTooBig)
+        ));
+    }
+
+    #[test]
+    fn test_unsigned_wrap_reinterprets_bit_pattern() {
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        to_writer_with_config(&mut buffer, &u32::MAX, Config::new().unsigned_wrap(true)).unwrap();
+        assert_eq!(buffer.into_inner(), (-1i32).to_be_bytes());
+
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        to_writer_with_config(&mut buffer, &u64::MAX, Config::new().unsigned_wrap(true)).unwrap();
+        assert_eq!(buffer.into_inner(), (-1i64).to_be_bytes());
+    }
+
+    #[test]
+    fn test_heterogeneous_sequence_errors() {
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        assert!(matches!(
+            to_writer(&mut buffer, &(1i32, "two")),
+            Err(Error::HeterogeneousSequence)
+        ));
+    }
+
+    #[test]
+    fn test_empty_sequence_still_writes_element_type_and_length() {
+        #[derive(Serialize)]
+        struct Wrapper {
+            list: Vec<i32>,
+        }
+
+        let mut buffer = Cursor::new(Vec::<u8>::new());
+        to_writer(&mut buffer, &Wrapper { list: Vec::new() }).unwrap();
+
+        assert_eq!(
+            buffer.into_inner(),
+            vec![
+                0x0a, 0x00, 0x00, // compound, empty name
+                0x09, // list
+                0x00, 0x04, b'l', b'i', b's', b't', // name "list"
+                0x00, // element type: TAG_End
+                0x00, 0x00, 0x00, 0x00, // length 0
+                0x00, // end tag
+            ]
+        );
+    }
+
+    #[test]
+    fn test_owned_tag_round_trips_through_to_vec() {
+        let mut fields = crate::nbt::Compound::new();
+        fields.insert(
+            "items".to_string(),
+            crate::nbt::owned::Tag::List(vec![
+                crate::nbt::owned::Tag::Int(1),
+                crate::nbt::owned::Tag::Int(2),
+            ]),
+        );
+        fields.insert(
+            "name".to_string(),
+            crate::nbt::owned::Tag::String("test".to_string()),
+        );
+        fields.insert("empty".to_string(), crate::nbt::owned::Tag::List(vec![]));
+        let tag = crate::nbt::owned::Tag::Compound(fields);
+
+        let bytes = to_vec(&tag).unwrap();
+        let round_tripped: crate::nbt::owned::Tag = crate::de::from_slice(&bytes).unwrap();
+
+        let crate::nbt::owned::Tag::Compound(round_tripped) = round_tripped else {
+            panic!("expected a compound");
+        };
+        assert!(matches!(
+            round_tripped["items"],
+            crate::nbt::owned::Tag::List(ref items) if items.len() == 2
+        ));
+        assert!(
+            matches!(round_tripped["name"], crate::nbt::owned::Tag::String(ref s) if s == "test")
+        );
+        assert!(
+            matches!(round_tripped["empty"], crate::nbt::owned::Tag::List(ref items) if items.is_empty())
+        );
+    }
 }
@@ -112,10 +112,15 @@ impl ser::Serializer for Serializer {
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        value.serialize(self)
+        match name {
+            crate::nbt::BYTE_ARRAY_TOKEN => Ok(TagType::ByteArray),
+            crate::nbt::INT_ARRAY_TOKEN => Ok(TagType::IntArray),
+            crate::nbt::LONG_ARRAY_TOKEN => Ok(TagType::LongArray),
+            _ => value.serialize(self),
+        }
     }
 
     fn serialize_newtype_variant<T: ?Sized + Serialize>(
@@ -0,0 +1,82 @@
+use super::Error;
+
+/// A minimal output sink for the serializer: just enough for `Formatter` to
+/// write bytes, so callers without a heap-backed `std::io::Write` (e.g. an
+/// embedded target writing into a fixed buffer) have somewhere to write to.
+/// `Serializer`/`Formatter`/`SeqSerializer`/`MapSerializer` are all generic
+/// over this trait rather than `std::io::Write` directly, with the blanket
+/// impl below making any existing `std::io::Write` type (a `Vec<u8>`, a
+/// `File`, a `Cursor`) usable as-is. Making the serializer itself work in
+/// `no_std` still needs an `alloc`/`std` Cargo feature split this crate
+/// doesn't have yet, but [`SliceWriter`] is already usable as a standalone
+/// sink for a caller with no heap.
+pub trait Write {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Error>;
+}
+
+impl<W: std::io::Write> Write for W {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+        std::io::Write::write_all(self, buf).map_err(Error::from)
+    }
+}
+
+/// Writes into a fixed-size `&mut [u8]` instead of an allocating sink, for
+/// callers without a heap. Returns [`Error::BufferFull`] instead of growing
+/// once `buf` is exhausted.
+pub struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, position: 0 }
+    }
+
+    /// The number of bytes written so far.
+    pub fn bytes_written(&self) -> usize {
+        self.position
+    }
+
+    pub fn into_inner(self) -> &'a mut [u8] {
+        self.buf
+    }
+}
+
+impl<'a> Write for SliceWriter<'a> {
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Error> {
+        let end = self
+            .position
+            .checked_add(buf.len())
+            .filter(|&end| end <= self.buf.len())
+            .ok_or(Error::BufferFull)?;
+        self.buf[self.position..end].copy_from_slice(buf);
+        self.position = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_writer() {
+        let mut buf = [0u8; 4];
+        let mut writer = SliceWriter::new(&mut buf);
+        writer.write_all(&[1, 2]).unwrap();
+        writer.write_all(&[3, 4]).unwrap();
+        assert_eq!(writer.bytes_written(), 4);
+        assert_eq!(writer.into_inner(), &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_slice_writer_buffer_full() {
+        let mut buf = [0u8; 2];
+        let mut writer = SliceWriter::new(&mut buf);
+        assert!(matches!(
+            writer.write_all(&[1, 2, 3]),
+            Err(Error::BufferFull)
+        ));
+    }
+}
@@ -20,4 +20,417 @@ mod nosimd;
 #[cfg(not(all(feature = "simd", any(feature = "nightly", target_arch = "x86_64"))))]
 pub(crate) use nosimd::*;
 
-mod tests;
+// On stable-channel aarch64 (simd enabled, nightly not, so the portable
+// std::simd backend above isn't in play), override the endianness swaps and
+// unpack4 pulled in by the nosimd glob above with NEON versions: unlike
+// AVX2/AVX-512, NEON is part of the aarch64 baseline, so no runtime feature
+// detection tier is needed the way unpack_amd64's `Features` struct is. The
+// explicit imports below shadow the matching names from `nosimd::*`.
+// unpack5/unpackn stay on the scalar `nosimd` path: unlike unpack4's
+// byte-aligned nibbles, 5 (and most other bit widths) straddle byte
+// boundaries, needing the same kind of cross-lane gather/shift pattern
+// `unpack_amd64`'s AVX-512/AVX2 tiers use — not yet ported to NEON.
+#[cfg(all(feature = "simd", target_arch = "aarch64", not(feature = "nightly")))]
+mod unpack_aarch64;
+#[cfg(all(feature = "simd", target_arch = "aarch64", not(feature = "nightly")))]
+pub(crate) use unpack_aarch64::{swap_endianness_32bit, swap_endianness_64bit, unpack4};
+
+/// Bit-packing layout for palette container indices (block states, biomes).
+/// Sometimes called "dense"/"straddling" (`Spanning`) and "padded" (`Aligned`)
+/// packing elsewhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Layout {
+    /// Pre-1.16 layout: indices are packed end-to-end with no padding, so an
+    /// index may cross a long boundary.
+    Spanning,
+    /// 1.16+ (20w17a+) layout: each long holds `floor(64 / bits)` indices and
+    /// an index never crosses a long boundary, wasting the top `64 % bits`
+    /// bits of the long.
+    Aligned,
+}
+
+/// Unpacks `bits`-wide palette indices from a vec of bytes into a vec of
+/// 16-bit values, in the given [`Layout`]. Dispatches to the backend's
+/// [`unpack4`]/[`unpack5`]/[`unpackn`] for the aligned layout, since none of
+/// them support indices crossing a long boundary.
+pub(crate) fn unpack(src: &[u8], bits: usize, big_endian: bool, layout: Layout) -> Vec<u16> {
+    match layout {
+        Layout::Spanning => unpack_spanning(src, bits, big_endian),
+        Layout::Aligned => match bits {
+            4 => unpack4(src, big_endian),
+            5 => unpack5(src, big_endian),
+            _ => unpackn(src, bits, big_endian),
+        },
+    }
+}
+
+/// Packs `bits`-wide palette indices into little/big-endian long bytes, in
+/// the given [`Layout`]. Mirrors [`unpack`].
+pub(crate) fn pack(values: &[u16], bits: usize, big_endian: bool, layout: Layout) -> Vec<u8> {
+    match layout {
+        Layout::Spanning => pack_spanning(values, bits, big_endian),
+        Layout::Aligned => match bits {
+            4 => pack4(values, big_endian),
+            5 => pack5(values, big_endian),
+            _ => packn(values, bits, big_endian),
+        },
+    }
+}
+
+/// Names the first out-of-range element found by [`unpack_validated`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnpackError {
+    /// Index into the unpacked output of the first out-of-range value.
+    pub offset: usize,
+    /// The out-of-range value itself.
+    pub value: u16,
+    /// The palette length it was checked against.
+    pub palette_len: u16,
+}
+
+/// Unpacks `bits`-wide palette indices from `src`, in the given [`Layout`],
+/// truncates to the first `count` of them (the aligned layout pads its last
+/// long with extra slots that aren't real elements and shouldn't be checked,
+/// same as [`unpack_and_histogram`]), then checks every remaining value
+/// against `palette_len`. Returns `Err(UnpackError)` naming the first
+/// out-of-range element instead of trusting the packed data, so callers can
+/// reject corrupt chunk data cheaply.
+///
+/// On the portable SIMD backend, 4-bit indices (which leave no trailing
+/// padding per long, since `64 % 4 == 0`) are checked with the fused
+/// `unpack4_validated` instead, as long as `count` already covers every
+/// unpacked value — `try_unpack_data` always calls this with a full section
+/// (a multiple of 16 values), so that's the common case in practice.
+pub(crate) fn unpack_validated(
+    src: &[u8],
+    bits: usize,
+    big_endian: bool,
+    layout: Layout,
+    count: usize,
+    palette_len: u16,
+) -> Result<Vec<u16>, UnpackError> {
+    #[cfg(all(feature = "simd", feature = "nightly", not(target_arch = "x86_64")))]
+    if bits == 4 && layout == Layout::Aligned && count == src.len() * 2 {
+        return unpack4_validated(src, big_endian, palette_len).map_err(
+            |Unpack4Error {
+                 offset,
+                 value,
+                 palette_len,
+             }| UnpackError {
+                offset,
+                value,
+                palette_len,
+            },
+        );
+    }
+
+    let mut unpacked = unpack(src, bits, big_endian, layout);
+    unpacked.truncate(count);
+
+    for (offset, &value) in unpacked.iter().enumerate() {
+        if value >= palette_len {
+            return Err(UnpackError {
+                offset,
+                value,
+                palette_len,
+            });
+        }
+    }
+
+    Ok(unpacked)
+}
+
+/// Maps each palette index in `indices` to its palette value. This is the
+/// general fallback for palettes that aren't byte-sized integers (block
+/// state palette entries, biome names). The portable SIMD backend also has a
+/// fused `unpack_and_remap4_u8` for small byte-valued palettes, but nothing
+/// in this crate stores a `u8`-valued palette today — [`BlockStates`] and
+/// [`Biomes`] both hold struct/string palettes — so it isn't wired in here;
+/// it stays available (and tested) for a future byte-valued palette to use
+/// directly.
+///
+/// [`BlockStates`]: crate::chunk_format::BlockStates
+/// [`Biomes`]: crate::chunk_format::Biomes
+pub(crate) fn remap<T: Clone>(indices: &[u16], palette: &[T]) -> Vec<T> {
+    indices
+        .iter()
+        .map(|&index| palette[index as usize].clone())
+        .collect()
+}
+
+/// Unpacks `bits`-wide palette indices from `src` and maps each one through
+/// `palette` in one call, so callers don't need to hold the intermediate
+/// index `Vec` themselves. Equivalent to `remap(&unpack(...), palette)`.
+pub(crate) fn unpack_and_remap<T: Clone>(
+    src: &[u8],
+    bits: usize,
+    big_endian: bool,
+    layout: Layout,
+    palette: &[T],
+) -> Vec<T> {
+    remap(&unpack(src, bits, big_endian, layout), palette)
+}
+
+/// Unpacks `bits`-wide palette indices from `src`, in the given [`Layout`],
+/// truncates to the first `count` of them (the aligned layout pads its last
+/// long with extra slots that aren't real elements, same as callers of
+/// [`unpack`] already truncate themselves), and tallies how often each
+/// possible index value occurs alongside it, so callers doing a block/biome
+/// census don't need a second pass over the unpacked data themselves.
+/// `counts` has `1 << bits` entries; `counts[v]` is the number of times
+/// value `v` appeared in the (truncated) output. If [`unpack`] yields no
+/// values at all (empty or malformed `src`, or `bits` out of range), both
+/// returned vecs are empty.
+pub(crate) fn unpack_and_histogram(
+    src: &[u8],
+    bits: usize,
+    big_endian: bool,
+    layout: Layout,
+    count: usize,
+) -> (Vec<u16>, Vec<u32>) {
+    let mut unpacked = unpack(src, bits, big_endian, layout);
+    if unpacked.is_empty() {
+        return (unpacked, Vec::new());
+    }
+    unpacked.truncate(count);
+
+    let mut counts = vec![0u32; 1usize << bits];
+    for &value in &unpacked {
+        counts[value as usize] += 1;
+    }
+
+    (unpacked, counts)
+}
+
+/// Packs `bits`-wide values into a vec of bytes, in the legacy (pre-1.16)
+/// spanning layout where indices are packed tightly with no regard for long
+/// boundaries and so may cross them. Inverse of [`unpack_spanning`]. Returns
+/// an empty vec if the input is empty or `bits` is not in `1..=16`.
+fn pack_spanning(values: &[u16], bits: usize, big_endian: bool) -> Vec<u8> {
+    if values.is_empty() || !(1..=16).contains(&bits) {
+        return Vec::new();
+    }
+
+    let bit_count = values.len() * bits;
+    let long_count = bit_count.div_ceil(64);
+    let mask = (1u64 << bits) - 1;
+    let mut longs = vec![0u64; long_count];
+
+    for (i, &value) in values.iter().enumerate() {
+        let bit_offset = i * bits;
+        let long_index = bit_offset / 64;
+        let shift = bit_offset % 64;
+        let value = value as u64 & mask;
+
+        longs[long_index] |= value << shift;
+        if shift + bits > 64 {
+            longs[long_index + 1] |= value >> (64 - shift);
+        }
+    }
+
+    longs
+        .iter()
+        .flat_map(|long| {
+            if big_endian {
+                long.to_be_bytes()
+            } else {
+                long.to_le_bytes()
+            }
+        })
+        .collect()
+}
+
+/// Unpacks `bits`-wide values from a vec of bytes into a vec of 16-bit
+/// values, in the legacy (pre-1.16) spanning layout where indices are packed
+/// tightly with no regard for long boundaries and so may cross them. Returns
+/// an empty vec if the input length is not a multiple of 8, is 0, or `bits`
+/// is not in `1..=16`.
+fn unpack_spanning(src: &[u8], bits: usize, big_endian: bool) -> Vec<u16> {
+    if src.len() % 8 != 0 || src.is_empty() || !(1..=16).contains(&bits) {
+        return Vec::new();
+    }
+
+    let longs: Vec<u64> = src
+        .chunks_exact(8)
+        .map(|chunk| {
+            let bytes: [u8; 8] = chunk.try_into().unwrap();
+            if big_endian {
+                u64::from_be_bytes(bytes)
+            } else {
+                u64::from_le_bytes(bytes)
+            }
+        })
+        .collect();
+
+    let count = longs.len() * 64 / bits;
+    let mask = (1u64 << bits) - 1;
+    let mut dst = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let bit_offset = i * bits;
+        let long_index = bit_offset / 64;
+        let shift = bit_offset % 64;
+
+        let low = longs[long_index] >> shift;
+        let value = if shift + bits > 64 {
+            let high = longs[long_index + 1];
+            (low | (high << (64 - shift))) & mask
+        } else {
+            low & mask
+        };
+
+        dst.push(value as u16);
+    }
+
+    dst
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use super::*;
+
+    #[test]
+    fn test_unpack_spanning_straddles_long_boundary() {
+        // 5 bits per value, 64 values: 320 bits = 5 longs, and 64 isn't a
+        // multiple of 5 so values repeatedly straddle long boundaries.
+        let values: Vec<u16> = (0..64).map(|i| i % 32).collect();
+        let src = pack_spanning(&values, 5, false);
+
+        assert_eq!(unpack_spanning(&src, 5, false), values);
+    }
+
+    #[test]
+    fn test_pack_spanning_round_trips_big_endian() {
+        let values: Vec<u16> = (0..64).map(|i| i % 32).collect();
+        let src = pack_spanning(&values, 5, true);
+
+        assert_eq!(unpack_spanning(&src, 5, true), values);
+    }
+
+    #[test]
+    fn test_unpack_spanning_invalid_input() {
+        assert!(unpack_spanning(&[], 5, false).is_empty());
+        assert!(unpack_spanning(&[0; 7], 5, false).is_empty());
+        assert!(unpack_spanning(&[0; 8], 17, false).is_empty());
+    }
+
+    #[test]
+    fn test_pack_spanning_invalid_input() {
+        assert!(pack_spanning(&[], 5, false).is_empty());
+        assert!(pack_spanning(&[0; 8], 17, false).is_empty());
+    }
+
+    #[test]
+    fn test_unpack_dispatches_on_layout() {
+        let values: Vec<u16> = (0..64).map(|i| i % 32).collect();
+        let spanning_src = pack_spanning(&values, 5, false);
+        assert_eq!(unpack(&spanning_src, 5, false, Layout::Spanning), values);
+
+        // 4-bit values never straddle a long boundary, so the same packing
+        // is valid for both layouts and should agree with unpack4 directly.
+        let values: Vec<u16> = (0..16).map(|i| i as u16).collect();
+        let aligned_src = pack_spanning(&values, 4, false);
+        assert_eq!(
+            unpack(&aligned_src, 4, false, Layout::Aligned),
+            unpack4(&aligned_src, false)
+        );
+    }
+
+    #[test]
+    fn test_remap_maps_indices_to_palette_values() {
+        let palette = ["air", "stone", "dirt", "grass"];
+        let indices = [0u16, 2, 3, 1, 0];
+
+        assert_eq!(
+            remap(&indices, &palette),
+            vec!["air", "dirt", "grass", "stone", "air"]
+        );
+    }
+
+    #[test]
+    fn test_unpack_and_remap_matches_unpack_then_remap() {
+        let palette: Vec<u32> = (0..16).collect();
+        let values: Vec<u16> = (0..16).map(|i| i as u16).collect();
+        let src = pack_spanning(&values, 4, false);
+
+        assert_eq!(
+            unpack_and_remap(&src, 4, false, Layout::Aligned, &palette),
+            remap(&unpack(&src, 4, false, Layout::Aligned), &palette)
+        );
+    }
+
+    #[test]
+    fn test_unpack_and_histogram_tallies_value_occurrences() {
+        let values: Vec<u16> = (0..16).map(|i| (i % 4) as u16).collect();
+        let src = pack_spanning(&values, 4, false);
+
+        let (unpacked, counts) =
+            unpack_and_histogram(&src, 4, false, Layout::Aligned, values.len());
+
+        assert_eq!(unpacked, values);
+        assert_eq!(counts.len(), 16);
+        assert_eq!(&counts[0..4], &[4, 4, 4, 4]);
+        assert!(counts[4..].iter().all(|&count| count == 0));
+    }
+
+    #[test]
+    fn test_unpack_and_histogram_truncates_to_count() {
+        let values: Vec<u16> = (0..16).map(|i| (i % 4) as u16).collect();
+        let src = pack_spanning(&values, 4, false);
+
+        let (unpacked, counts) = unpack_and_histogram(&src, 4, false, Layout::Aligned, 4);
+
+        assert_eq!(unpacked, values[..4]);
+        assert_eq!(&counts[0..4], &[1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_unpack_and_histogram_invalid_input() {
+        assert_eq!(
+            unpack_and_histogram(&[], 4, false, Layout::Aligned, 0),
+            (Vec::new(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_unpack_validated_accepts_in_range_values() {
+        let values: Vec<u16> = (0..16).map(|i| i as u16).collect();
+        let src = pack_spanning(&values, 4, false);
+
+        assert_eq!(
+            unpack_validated(&src, 4, false, Layout::Aligned, values.len(), 16),
+            Ok(values)
+        );
+    }
+
+    #[test]
+    fn test_unpack_validated_rejects_out_of_range_values() {
+        let mut values: Vec<u16> = vec![0; 16];
+        values[5] = 9;
+        let src = pack_spanning(&values, 4, false);
+
+        assert_eq!(
+            unpack_validated(&src, 4, false, Layout::Aligned, values.len(), 9),
+            Err(UnpackError {
+                offset: 5,
+                value: 9,
+                palette_len: 9,
+            })
+        );
+    }
+
+    #[test]
+    fn test_pack_dispatches_on_layout() {
+        let values: Vec<u16> = (0..64).map(|i| i % 32).collect();
+        assert_eq!(
+            pack(&values, 5, false, Layout::Spanning),
+            pack_spanning(&values, 5, false)
+        );
+
+        let values: Vec<u16> = (0..16).map(|i| i as u16).collect();
+        assert_eq!(
+            pack(&values, 4, false, Layout::Aligned),
+            pack4(&values, false)
+        );
+    }
+}
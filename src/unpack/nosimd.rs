@@ -80,6 +80,136 @@ pub(crate) fn unpack5(src: &[u8], big_endian: bool) -> Vec<u16> {
     dst
 }
 
+/// Unpacks `bits`-wide values from a vec of bytes into a vec of 16-bit values.
+/// Generalizes [`unpack4`] and [`unpack5`] to any bit width in `1..=16`, for
+/// the palette sizes those two don't specialize for. Returns an empty vec if
+/// the input length is not a multiple of 8, is 0, or `bits` is out of range.
+pub(crate) fn unpackn(src: &[u8], bits: usize, big_endian: bool) -> Vec<u16> {
+    if src.len() % 8 != 0 || src.is_empty() || !(1..=16).contains(&bits) {
+        return Vec::new();
+    }
+
+    let values_per_long = 64 / bits;
+    let mut dst: Vec<u16> = vec![0; src.len() / 8 * values_per_long];
+
+    for i in (0..src.len()).step_by(8) {
+        let long = if big_endian {
+            u64::from_be_bytes([
+                src[i],
+                src[i + 1],
+                src[i + 2],
+                src[i + 3],
+                src[i + 4],
+                src[i + 5],
+                src[i + 6],
+                src[i + 7],
+            ])
+        } else {
+            u64::from_le_bytes([
+                src[i],
+                src[i + 1],
+                src[i + 2],
+                src[i + 3],
+                src[i + 4],
+                src[i + 5],
+                src[i + 6],
+                src[i + 7],
+            ])
+        };
+        for j in 0..values_per_long {
+            dst[i / 8 * values_per_long + j] = ((long >> (j * bits)) & ((1 << bits) - 1)) as u16;
+        }
+    }
+
+    dst
+}
+
+/// Packs 4-bit values into a vec of bytes. Inverse of [`unpack4`]. Returns an
+/// empty vec if the input length is not a multiple of 16 or is 0.
+pub(crate) fn pack4(values: &[u16], big_endian: bool) -> Vec<u8> {
+    if values.len() % 16 != 0 || values.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dst: Vec<u8> = vec![0; values.len() / 2];
+
+    for i in (0..values.len()).step_by(16) {
+        let mut long: u64 = 0;
+        for j in 0..16 {
+            long |= (values[i + j] as u64 & 0x0f) << (j * 4);
+        }
+
+        let bytes = if big_endian {
+            long.to_be_bytes()
+        } else {
+            long.to_le_bytes()
+        };
+        dst[i / 2..i / 2 + 8].copy_from_slice(&bytes);
+    }
+
+    dst
+}
+
+/// Packs 5-bit values into a vec of bytes. Inverse of [`unpack5`]. Returns an
+/// empty vec if the input length is not a multiple of 12 or is 0.
+pub(crate) fn pack5(values: &[u16], big_endian: bool) -> Vec<u8> {
+    if values.len() % 12 != 0 || values.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dst: Vec<u8> = vec![0; values.len() / 12 * 8];
+
+    for i in (0..values.len()).step_by(12) {
+        let mut long: u64 = 0;
+        for j in 0..12 {
+            long |= (values[i + j] as u64 & 0x1f) << (j * 5);
+        }
+
+        let bytes = if big_endian {
+            long.to_be_bytes()
+        } else {
+            long.to_le_bytes()
+        };
+        dst[i / 12 * 8..i / 12 * 8 + 8].copy_from_slice(&bytes);
+    }
+
+    dst
+}
+
+/// Packs `bits`-wide values into a vec of bytes. Generalizes [`pack4`] and
+/// [`pack5`] to any bit width in `1..=16`, mirroring [`unpackn`]. Returns an
+/// empty vec if the input length is not a multiple of `64 / bits`, is 0, or
+/// `bits` is out of range.
+pub(crate) fn packn(values: &[u16], bits: usize, big_endian: bool) -> Vec<u8> {
+    if !(1..=16).contains(&bits) {
+        return Vec::new();
+    }
+
+    let values_per_long = 64 / bits;
+    if values.len() % values_per_long != 0 || values.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dst: Vec<u8> = vec![0; values.len() / values_per_long * 8];
+
+    for i in (0..values.len()).step_by(values_per_long) {
+        let mut long: u64 = 0;
+        for j in 0..values_per_long {
+            long |= (values[i + j] as u64 & ((1 << bits) - 1)) << (j * bits);
+        }
+
+        let bytes = if big_endian {
+            long.to_be_bytes()
+        } else {
+            long.to_le_bytes()
+        };
+        let dst_offset = i / values_per_long * 8;
+        dst[dst_offset..dst_offset + 8].copy_from_slice(&bytes);
+    }
+
+    dst
+}
+
 /// Swaps the endianess of 32-bit values in a vec of bytes. Returns an empty vec
 /// if the input length is not a multiple of 4 or is 0.
 pub(crate) fn swap_endianness_32bit(src: &[u8]) -> Vec<u8> {
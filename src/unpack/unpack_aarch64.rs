@@ -0,0 +1,152 @@
+use std::arch::aarch64::*;
+
+/// Unpacks 4-bit values from a vec of bytes into a vec of 16-bit values.
+/// Returns an empty vec if the input length is not a multiple of 8 or is 0.
+///
+/// NEON is part of the aarch64 baseline, so like [`swap_endianness_32bit`]
+/// this needs no runtime feature detection. Each 128-bit register holds two
+/// input longs (16 bytes); `vzip1q_u8`/`vzip2q_u8` interleave the low and
+/// high nibbles of each byte into the output order directly, one zip result
+/// per long, mirroring the `_mm_unpacklo_epi8`/`_mm_unpackhi_epi8` step of
+/// the SSE4.2 tier in `unpack_amd64`.
+pub(crate) fn unpack4(src: &[u8], big_endian: bool) -> Vec<u16> {
+    if src.len() % 8 != 0 || src.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dst: Vec<u16> = vec![0; src.len() * 2];
+    let mut offset: usize = 0;
+
+    unsafe {
+        let lower_nibble_mask = vdupq_n_u8(0x0f);
+
+        while offset + 16 <= src.len() {
+            let mut bytes = vld1q_u8(src.as_ptr().add(offset));
+            if big_endian {
+                bytes = vrev64q_u8(bytes);
+            }
+
+            let lower = vandq_u8(bytes, lower_nibble_mask);
+            let upper = vshrq_n_u8(bytes, 4);
+
+            // each zip result interleaves one whole 64-bit (one long's worth
+            // of bytes) lane of `lower`/`upper`, giving the 16 output values
+            // for that long directly.
+            let long0 = vzip1q_u8(lower, upper);
+            let long1 = vzip2q_u8(lower, upper);
+
+            vst1q_u16(
+                dst.as_mut_ptr().add(offset * 2),
+                vmovl_u8(vget_low_u8(long0)),
+            );
+            vst1q_u16(
+                dst.as_mut_ptr().add(offset * 2 + 8),
+                vmovl_u8(vget_high_u8(long0)),
+            );
+            vst1q_u16(
+                dst.as_mut_ptr().add(offset * 2 + 16),
+                vmovl_u8(vget_low_u8(long1)),
+            );
+            vst1q_u16(
+                dst.as_mut_ptr().add(offset * 2 + 24),
+                vmovl_u8(vget_high_u8(long1)),
+            );
+
+            offset += 16;
+        }
+    }
+
+    while offset + 8 <= src.len() {
+        for i in 0..8 {
+            let endian_offset = if big_endian {
+                offset + (7 - i)
+            } else {
+                offset + i
+            };
+            dst[(offset + i) * 2] = (src[endian_offset] & 0x0f) as u16;
+            dst[(offset + i) * 2 + 1] = ((src[endian_offset] & 0xf0) >> 4) as u16;
+        }
+
+        offset += 8;
+    }
+
+    dst
+}
+
+/// Swaps the endianess of 32-bit values in a vec of bytes. Returns an empty vec
+/// if the input length is not a multiple of 4 or is 0.
+///
+/// NEON is part of the aarch64 baseline, so unlike the amd64 backend this
+/// needs no runtime feature detection: `vrev32q_u8` reverses the bytes
+/// within each 32-bit lane in one instruction, with no permutation mask to
+/// get wrong.
+pub(crate) fn swap_endianness_32bit(src: &[u8]) -> Vec<u8> {
+    if src.len() % 4 != 0 || src.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dst: Vec<u8> = vec![0; src.len()];
+    let mut offset: usize = 0;
+
+    unsafe {
+        while offset + 16 <= src.len() {
+            let bytes = vld1q_u8(src.as_ptr().add(offset));
+            let swapped = vrev32q_u8(bytes);
+            vst1q_u8(dst.as_mut_ptr().add(offset), swapped);
+
+            offset += 16;
+        }
+    }
+
+    while offset + 4 <= src.len() {
+        dst[offset..offset + 4].copy_from_slice(&[
+            src[offset + 3],
+            src[offset + 2],
+            src[offset + 1],
+            src[offset],
+        ]);
+
+        offset += 4;
+    }
+
+    dst
+}
+
+/// Swaps the endianess of 64-bit values in a vec of bytes. Returns an empty vec
+/// if the input length is not a multiple of 8 or is 0. See
+/// [`swap_endianness_32bit`] for why this needs no runtime feature detection.
+pub(crate) fn swap_endianness_64bit(src: &[u8]) -> Vec<u8> {
+    if src.len() % 8 != 0 || src.is_empty() {
+        return Vec::new();
+    }
+
+    let mut dst: Vec<u8> = vec![0; src.len()];
+    let mut offset: usize = 0;
+
+    unsafe {
+        while offset + 16 <= src.len() {
+            let bytes = vld1q_u8(src.as_ptr().add(offset));
+            let swapped = vrev64q_u8(bytes);
+            vst1q_u8(dst.as_mut_ptr().add(offset), swapped);
+
+            offset += 16;
+        }
+    }
+
+    while offset + 8 <= src.len() {
+        dst[offset..offset + 8].copy_from_slice(&[
+            src[offset + 7],
+            src[offset + 6],
+            src[offset + 5],
+            src[offset + 4],
+            src[offset + 3],
+            src[offset + 2],
+            src[offset + 1],
+            src[offset],
+        ]);
+
+        offset += 8;
+    }
+
+    dst
+}